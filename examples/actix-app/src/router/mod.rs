@@ -3,7 +3,7 @@ use crate::{
     middleware,
 };
 use actix_web::web::{get, post, scope, ServiceConfig};
-use zino::{DefaultController, RouterConfigure};
+use zino::{prelude::Uuid, DefaultController, RouterConfigure, TenantContextInitializer};
 use zino_model::{Tag, User};
 
 pub fn routes() -> Vec<RouterConfigure> {
@@ -26,6 +26,7 @@ fn auth_router(cfg: &mut ServiceConfig) {
         scope("/auth")
             .route("/refresh", get().to(auth::refresh))
             .route("/logout", post().to(auth::logout))
+            .wrap(TenantContextInitializer::<Uuid>::default())
             .wrap(middleware::UserSessionInitializer),
     );
 }
@@ -35,6 +36,7 @@ fn file_router(cfg: &mut ServiceConfig) {
         scope("/file")
             .route("/upload", post().to(file::upload))
             .route("/decrypt", get().to(file::decrypt))
+            .wrap(TenantContextInitializer::<Uuid>::default())
             .wrap(middleware::UserSessionInitializer),
     );
 }
@@ -47,8 +49,10 @@ fn user_router(cfg: &mut ServiceConfig) {
             .route("/{id}/update", post().to(User::update))
             .route("/{id}/view", get().to(user::view))
             .route("/list", get().to(User::list))
+            .route("/stats", get().to(User::stats))
             .route("/import", post().to(User::import))
             .route("/export", get().to(User::export))
+            .wrap(TenantContextInitializer::<Uuid>::default())
             .wrap(middleware::UserSessionInitializer),
     );
 }
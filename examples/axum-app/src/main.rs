@@ -12,8 +12,12 @@ mod schedule;
 mod service;
 
 use zino::prelude::*;
+use zino_model::{Tag, User};
 
 fn main() {
+    User::register_openapi_schema();
+    Tag::register_openapi_schema();
+
     zino::Cluster::boot()
         .register(router::routes())
         .register_debug(router::debug_routes())
@@ -14,6 +14,9 @@ pub async fn init_user_session(mut req: Request, next: Next<Body>) -> Result<Res
                 if let Ok(session_id) = req.parse_session_id() {
                     user_session.set_session_id(session_id);
                 }
+                let mut audit_context = AuditContext::new();
+                audit_context.set_actor(user_session.user_id());
+                req.set_data(audit_context);
                 req.set_data(user_session);
             } else {
                 reject!(req, unauthorized, "invalid JWT claims");
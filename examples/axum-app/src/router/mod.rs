@@ -7,7 +7,7 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use zino::DefaultController;
+use zino::{prelude::Uuid, tenant_context, DefaultController};
 use zino_model::{Tag, User};
 
 pub fn routes() -> Vec<Router> {
@@ -18,6 +18,7 @@ pub fn routes() -> Vec<Router> {
         Router::new()
             .route("/auth/refresh", get(auth::refresh))
             .route("/auth/logout", post(auth::logout))
+            .layer(from_fn(tenant_context::<Uuid>))
             .layer(from_fn(middleware::init_user_session)),
     );
     routes.push(router);
@@ -26,6 +27,7 @@ pub fn routes() -> Vec<Router> {
     let router = Router::new()
         .route("/file/upload", post(file::upload))
         .route("/file/decrypt", get(file::decrypt))
+        .layer(from_fn(tenant_context::<Uuid>))
         .layer(from_fn(middleware::init_user_session));
     routes.push(router);
 
@@ -36,8 +38,10 @@ pub fn routes() -> Vec<Router> {
         .route("/user/:id/update", post(User::update))
         .route("/user/:id/view", get(user::view))
         .route("/user/list", get(User::list))
+        .route("/user/stats", get(User::stats))
         .route("/user/import", post(User::import))
         .route("/user/export", get(User::export))
+        .layer(from_fn(tenant_context::<Uuid>))
         .layer(from_fn(middleware::init_user_session));
     routes.push(router);
 
@@ -317,6 +317,9 @@ where
         let mut mutation = Self::default_mutation();
         let mut updates = self.next_edition_updates();
         updates.upsert("status", "Deleted");
+        if Self::has_column("deleted_at") {
+            updates.upsert("deleted_at", DateTime::now().to_utc_timestamp());
+        }
         mutation.append_updates(&mut updates);
         mutation
     }
@@ -348,7 +351,6 @@ where
     fn default_list_query() -> Query {
         let mut query = Self::default_query();
         query.deny_fields(&["content", "extra"]);
-        query.add_filter("status", Map::from_entry("$ne", "Deleted"));
         query.set_sort_order("updated_at", true);
         query
     }
@@ -383,6 +385,16 @@ where
         Ok(model)
     }
 
+    /// Fetches the data of a model selected by the primary key,
+    /// and eager loads the relations included in the `Query`.
+    ///
+    /// The default implementation ignores the `Query`, and only the derived
+    /// `ModelAccessor` implementation eager loads the included relations.
+    #[inline]
+    async fn fetch_by_id_with(id: &K, _query: &Query) -> Result<Map, Error> {
+        Self::fetch_by_id(id).await
+    }
+
     /// Deletes a model of the primary key by setting the status as `Deleted`.
     async fn soft_delete_by_id(id: &K) -> Result<(), Error> {
         let mut model = Self::try_get_model(id).await?;
@@ -423,7 +435,8 @@ where
 
         let mut model = Self::try_get_model(id).await?;
         if let Some(version) = data.get_u64("version") && model.version() != version {
-            return Err(Error::new("409 Conflict: there is a version control conflict"));
+            let message = "409 Conflict: there is a version control conflict";
+            return Err(Error::new(message).with_status_code(409));
         }
         Self::before_validation(data, extension.as_ref()).await?;
 
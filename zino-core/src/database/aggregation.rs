@@ -0,0 +1,102 @@
+use super::{query::QueryExt, DatabaseDriver, Schema};
+use crate::model::{AggregateFunction, Aggregation, DateBucket, Query};
+
+/// Extension trait for [`Aggregation`](crate::model::Aggregation),
+/// which generates SQL `SELECT` expressions and the `GROUP BY` clause.
+pub(super) trait AggregationExt<DB> {
+    /// Formats the aggregation to generate SQL `SELECT` expressions and the `GROUP BY` clause.
+    /// The fields which are not the columns of the model are ignored,
+    /// and the number of rows is counted if there are no valid aggregate expressions.
+    fn format_aggregation<M: Schema>(&self) -> (String, String);
+}
+
+impl AggregationExt<DatabaseDriver> for Aggregation {
+    fn format_aggregation<M: Schema>(&self) -> (String, String) {
+        let mut expressions = Vec::new();
+        let mut groups = Vec::new();
+        for (field, bucket) in self.groups() {
+            if M::has_column(field) {
+                let expr = Query::format_field(field);
+                let (alias, expr) = if let Some(bucket) = bucket {
+                    let unit = bucket.as_str();
+                    (
+                        format!("{field}_{unit}"),
+                        format_date_bucket(&expr, *bucket),
+                    )
+                } else {
+                    (field.to_owned(), expr.into_owned())
+                };
+                let alias = Query::format_field(&alias);
+                expressions.push(format!("{expr} AS {alias}"));
+                groups.push(expr);
+            }
+        }
+
+        // Casts the results of `sum` and `avg` to the float type
+        // so that they can be decoded in the same way for all backends.
+        let float_type = if cfg!(feature = "orm-mysql") {
+            "DOUBLE"
+        } else if cfg!(feature = "orm-postgres") {
+            "DOUBLE PRECISION"
+        } else {
+            "REAL"
+        };
+        let num_groups = expressions.len();
+        for (function, field) in self.aggregates() {
+            if field == "*" && *function == AggregateFunction::Count {
+                let alias = Query::format_field("count");
+                expressions.push(format!("count(*) AS {alias}"));
+            } else if M::has_column(field) {
+                let name = function.as_str();
+                let alias = Query::format_field(&format!("{field}_{name}")).into_owned();
+                let field = Query::format_field(field);
+                let expr = match function {
+                    AggregateFunction::Count => format!("count({field})"),
+                    AggregateFunction::CountDistinct => format!("count(DISTINCT {field})"),
+                    AggregateFunction::Sum => format!("CAST(sum({field}) AS {float_type})"),
+                    AggregateFunction::Avg => format!("CAST(avg({field}) AS {float_type})"),
+                    AggregateFunction::Min => format!("min({field})"),
+                    AggregateFunction::Max => format!("max({field})"),
+                };
+                expressions.push(format!("{expr} AS {alias}"));
+            }
+        }
+        if expressions.len() == num_groups {
+            let alias = Query::format_field("count");
+            expressions.push(format!("count(*) AS {alias}"));
+        }
+
+        let groups = if groups.is_empty() {
+            String::new()
+        } else {
+            let groups = groups.join(", ");
+            format!("GROUP BY {groups} ORDER BY {groups}")
+        };
+        (expressions.join(", "), groups)
+    }
+}
+
+/// Formats the date bucket of a field as a string such as `2023-08`.
+fn format_date_bucket(field: &str, bucket: DateBucket) -> String {
+    if cfg!(feature = "orm-postgres") {
+        let format = match bucket {
+            DateBucket::Year => "YYYY",
+            DateBucket::Month => "YYYY-MM",
+            DateBucket::Day => "YYYY-MM-DD",
+            DateBucket::Hour => "YYYY-MM-DD HH24:00",
+        };
+        format!("to_char({field}, '{format}')")
+    } else {
+        let format = match bucket {
+            DateBucket::Year => "%Y",
+            DateBucket::Month => "%Y-%m",
+            DateBucket::Day => "%Y-%m-%d",
+            DateBucket::Hour => "%Y-%m-%d %H:00",
+        };
+        if cfg!(feature = "orm-mysql") {
+            format!("DATE_FORMAT({field}, '{format}')")
+        } else {
+            format!("strftime('{format}', {field})")
+        }
+    }
+}
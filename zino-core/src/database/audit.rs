@@ -0,0 +1,565 @@
+use super::{
+    migration,
+    query::{self, QueryExt},
+    schema, scope, ConnectionPool, Schema, TransactionContext,
+};
+use crate::{
+    error::Error,
+    extension::JsonObjectExt,
+    model::{DecodeRow, EncodeColumn, Mutation, Query, QueryContext},
+    JsonValue, Map, Uuid,
+};
+use std::{cell::RefCell, collections::HashMap, fmt, future::Future};
+
+/// Actions recorded in the audit trail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    /// A model was inserted.
+    Insert,
+    /// A model was updated.
+    Update,
+    /// A model was updated or inserted.
+    Upsert,
+    /// A model was deleted.
+    Delete,
+    /// A model was logically deleted.
+    SoftDelete,
+    /// A model was restored to a past version.
+    Restore,
+}
+
+impl AuditAction {
+    /// Returns the name of the action.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Upsert => "upsert",
+            Self::Delete => "delete",
+            Self::SoftDelete => "soft_delete",
+            Self::Restore => "restore",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Context of the audit trail, which is shared by the model operations in a scope.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    /// The actor who makes the changes.
+    actor: Option<String>,
+    /// Request ID.
+    request_id: Option<Uuid>,
+}
+
+impl AuditContext {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the actor, which is usually the user ID of a [`UserSession`](crate::auth::UserSession).
+    #[inline]
+    pub fn set_actor(&mut self, actor: impl ToString) {
+        self.actor = Some(actor.to_string());
+    }
+
+    /// Sets the request ID.
+    #[inline]
+    pub fn set_request_id(&mut self, request_id: Uuid) {
+        self.request_id = (!request_id.is_nil()).then_some(request_id);
+    }
+
+    /// Returns the actor.
+    #[inline]
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Returns the request ID.
+    #[inline]
+    pub fn request_id(&self) -> Option<Uuid> {
+        self.request_id
+    }
+
+    /// Returns the audit context of the current scope.
+    #[inline]
+    pub fn current() -> Option<Self> {
+        CURRENT_AUDIT_CONTEXT.with(|ctx| ctx.borrow().clone())
+    }
+
+    /// Runs the future with the audit context as the context of the current scope.
+    #[inline]
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        scope::run(&CURRENT_AUDIT_CONTEXT, self, future).await
+    }
+}
+
+/// An entry of the audit trail, which can be used as the model data of [`ModelHooks`].
+/// It is captured in the `before_*` hooks and recorded in the `after_*` hooks.
+///
+/// The snapshots are fetched from the connection pool, which can not see the uncommitted
+/// changes of a [`TransactionContext`], so the recording of the operations in a transaction
+/// is deferred until the transaction is committed.
+///
+/// [`ModelHooks`]: crate::model::ModelHooks
+#[derive(Debug, Clone, Default)]
+pub struct AuditEntry {
+    /// Model IDs and the snapshots of the models before the change.
+    snapshots: Vec<(String, Option<Map>)>,
+    /// Audit context.
+    context: AuditContext,
+}
+
+impl AuditEntry {
+    /// Creates a new instance for the model to be inserted,
+    /// which has no snapshot before the change.
+    #[inline]
+    pub fn new<M: Schema>(model: &M) -> Self {
+        Self {
+            snapshots: vec![(model.primary_key().to_string(), None)],
+            context: AuditContext::current().unwrap_or_default(),
+        }
+    }
+
+    /// Captures the snapshot of the model stored in the table before the change.
+    #[inline]
+    pub async fn capture<M: Schema>(model: &M) -> Result<Self, Error> {
+        Self::capture_by_id::<M>(model.primary_key().to_string()).await
+    }
+
+    /// Captures the snapshot of the model with the ID before the change.
+    async fn capture_by_id<M: Schema>(model_id: String) -> Result<Self, Error> {
+        let mut query = Query::new(Map::from_entry(M::PRIMARY_KEY_NAME, model_id.as_str()));
+        query.add_filter("show_deleted", true);
+
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<M>(Some(&mut arguments));
+        let table = SnapshotTable::new::<M>().await?;
+        let mut snapshots = table.fetch(&filters, &arguments).await?;
+        let before = snapshots.remove(&model_id);
+        Ok(Self {
+            snapshots: vec![(model_id, before)],
+            context: AuditContext::current().unwrap_or_default(),
+        })
+    }
+
+    /// Captures the snapshots of the models selected by the query before the change.
+    pub async fn capture_many<M: Schema>(query: &Query) -> Result<Self, Error> {
+        let mut arguments = Vec::new();
+        let filters = query.format_bound_filters::<M>(&mut arguments);
+        let table = SnapshotTable::new::<M>().await?;
+        let snapshots = table.fetch(&filters, &arguments).await?;
+        Ok(Self {
+            snapshots: snapshots
+                .into_iter()
+                .map(|(model_id, snapshot)| (model_id, Some(snapshot)))
+                .collect(),
+            context: AuditContext::current().unwrap_or_default(),
+        })
+    }
+
+    /// Records the changes of the models into the history table if the query is successful.
+    /// It also records the error and emits the metrics as the default `after_*` hooks.
+    ///
+    /// In a [`TransactionContext`], the changes are recorded after the commit.
+    pub async fn record<M: Schema>(
+        self,
+        ctx: &QueryContext,
+        action: AuditAction,
+    ) -> Result<(), Error> {
+        ctx.emit_metrics(action.as_str());
+        if !ctx.is_success() {
+            ctx.record_error(format!("fail to {action} the models in the table"));
+            return Ok(());
+        } else if self.snapshots.is_empty() {
+            return Ok(());
+        }
+
+        let batch = HistoryBatch {
+            table: SnapshotTable::new::<M>().await?,
+            history_table_name: M::history_table_name(),
+            action,
+            entry: self,
+        };
+        if let Some(task) = TransactionContext::defer(Box::pin(batch.write())) {
+            task.await?;
+        }
+        Ok(())
+    }
+}
+
+/// Table of the model snapshots, which does not depend on the model type.
+struct SnapshotTable {
+    /// Connection pool of the model writer.
+    pool: &'static ConnectionPool,
+    /// Qualified table name.
+    table_name: String,
+    /// Primary key name.
+    primary_key_name: &'static str,
+    /// Type to cast the placeholders of the primary key for PostgreSQL.
+    primary_key_type: Option<&'static str>,
+    /// Projection of all the columns except the write-only ones.
+    projection: String,
+}
+
+impl SnapshotTable {
+    /// Creates a new instance for the model.
+    async fn new<M: Schema>() -> Result<Self, Error> {
+        let query = M::default_query();
+        Ok(Self {
+            pool: M::acquire_writer().await?,
+            table_name: M::qualified_table_name().into_owned(),
+            primary_key_name: M::PRIMARY_KEY_NAME,
+            primary_key_type: M::get_column(M::PRIMARY_KEY_NAME)
+                .filter(|_| cfg!(feature = "orm-postgres"))
+                .map(|col| migration::cast_column_type(col.column_type())),
+            projection: query.format_projection().into_owned(),
+        })
+    }
+
+    /// Fetches the snapshots of the models selected by the filters, keyed by the model IDs.
+    /// The `arguments` are bound to the placeholders in the filters.
+    async fn fetch(
+        &self,
+        filters: &str,
+        arguments: &[JsonValue],
+    ) -> Result<HashMap<String, Map>, Error> {
+        let table_name = &self.table_name;
+        let projection = &self.projection;
+        let sql = format!("SELECT {projection} FROM {table_name} {filters};");
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+        }
+        let rows = sqlx_query.fetch_all(self.pool.pool()).await?;
+        let mut snapshots = HashMap::with_capacity(rows.len());
+        for row in rows.iter() {
+            let snapshot = Map::decode_row(row)?;
+            if let Some(model_id) = snapshot.get_str(self.primary_key_name) {
+                snapshots.insert(model_id.to_owned(), snapshot);
+            } else if let Some(model_id) = snapshot.get(self.primary_key_name) {
+                snapshots.insert(model_id.to_string(), snapshot);
+            }
+        }
+        Ok(snapshots)
+    }
+}
+
+/// A batch of the changes to be written into the history table.
+/// It does not depend on the model type, so the writing can be deferred.
+struct HistoryBatch {
+    /// Table of the model snapshots.
+    table: SnapshotTable,
+    /// Name of the history table.
+    history_table_name: String,
+    /// Audit action.
+    action: AuditAction,
+    /// Audit entry.
+    entry: AuditEntry,
+}
+
+impl HistoryBatch {
+    /// Writes the changes of the models into the history table in one statement.
+    async fn write(self) -> Result<(), Error> {
+        let action = self.action;
+        let mut after_snapshots = if action == AuditAction::Delete {
+            HashMap::new()
+        } else {
+            let mut arguments = Vec::with_capacity(self.entry.snapshots.len());
+            let mut placeholders = Vec::with_capacity(self.entry.snapshots.len());
+            for (model_id, _) in self.entry.snapshots.iter() {
+                arguments.push(JsonValue::from(model_id.as_str()));
+                let placeholder = Query::placeholder(arguments.len());
+                if let Some(primary_key_type) = self.table.primary_key_type {
+                    placeholders.push(format!("{placeholder}::{primary_key_type}"));
+                } else {
+                    placeholders.push(placeholder.into_owned());
+                }
+            }
+
+            let primary_key_name = self.table.primary_key_name;
+            let placeholders = placeholders.join(", ");
+            let filters = format!("WHERE {primary_key_name} IN ({placeholders})");
+            self.table.fetch(&filters, &arguments).await?
+        };
+
+        let context = &self.entry.context;
+        let actor = context
+            .actor()
+            .map(Query::escape_string)
+            .unwrap_or_else(|| "NULL".to_owned());
+        let request_id = context
+            .request_id()
+            .map(Query::escape_string)
+            .unwrap_or_else(|| "NULL".to_owned());
+        let mut values = Vec::with_capacity(self.entry.snapshots.len());
+        for (model_id, before) in self.entry.snapshots {
+            let after = after_snapshots.remove(&model_id);
+            let changes = diff_snapshots(before.as_ref(), after.as_ref());
+            if changes.is_empty() && matches!(action, AuditAction::Update | AuditAction::Upsert) {
+                continue;
+            }
+
+            let id = Query::escape_string(Uuid::new_v4());
+            let model_id = Query::escape_string(&model_id);
+            let changes = Query::escape_string(JsonValue::from(changes));
+            let data = after
+                .or(before)
+                .map(|data| Query::escape_string(JsonValue::from(data)))
+                .unwrap_or_else(|| "NULL".to_owned());
+            values.push(format!(
+                "({id}, {model_id}, '{action}', {changes}, {data}, {actor}, {request_id})"
+            ));
+        }
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let history_table_name = self.history_table_name;
+        let values = values.join(", ");
+        let sql = format!(
+            "INSERT INTO {history_table_name} \
+                (id, model_id, action, changes, data, actor, request_id) VALUES {values};"
+        );
+        sqlx::query(&sql).execute(self.table.pool.pool()).await?;
+        Ok(())
+    }
+}
+
+/// Audit trail of the model changes recorded in the `{table_name}_history` table.
+/// The changes are recorded by the model hooks with [`AuditEntry`] as the model data,
+/// and the actor and request ID are provided by the [`AuditContext`] of the current scope.
+pub trait AuditTrail: Schema {
+    /// Returns the name of the history table.
+    #[inline]
+    fn history_table_name() -> String {
+        format!("{}_history", Self::qualified_table_name())
+    }
+
+    /// Creates the history table if it does not exist.
+    async fn create_history_table() -> Result<(), Error> {
+        let pool = Self::init_writer()?.pool();
+        let history_table_name = Self::history_table_name();
+        let statements = if cfg!(feature = "orm-mysql") {
+            vec![format!(
+                "CREATE TABLE IF NOT EXISTS {history_table_name} (
+                    id VARCHAR(36) PRIMARY KEY,
+                    model_id VARCHAR(255) NOT NULL,
+                    action VARCHAR(32) NOT NULL,
+                    changes JSON,
+                    data JSON,
+                    actor VARCHAR(255),
+                    request_id VARCHAR(36),
+                    created_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+                    INDEX (model_id, created_at)
+                );"
+            )]
+        } else {
+            let (uuid_type, json_type, datetime_type, current_timestamp) =
+                if cfg!(feature = "orm-postgres") {
+                    ("UUID", "JSONB", "TIMESTAMPTZ", "now()")
+                } else {
+                    (
+                        "TEXT",
+                        "TEXT",
+                        "TEXT",
+                        "(strftime('%Y-%m-%d %H:%M:%f', 'now'))",
+                    )
+                };
+            vec![
+                format!(
+                    "CREATE TABLE IF NOT EXISTS {history_table_name} (
+                        id {uuid_type} PRIMARY KEY,
+                        model_id TEXT NOT NULL,
+                        action TEXT NOT NULL,
+                        changes {json_type},
+                        data {json_type},
+                        actor TEXT,
+                        request_id {uuid_type},
+                        created_at {datetime_type} NOT NULL DEFAULT {current_timestamp}
+                    );"
+                ),
+                format!(
+                    "CREATE INDEX IF NOT EXISTS {table_name}_history_model_id_index \
+                        ON {history_table_name} (model_id, created_at);",
+                    table_name = Self::table_name(),
+                ),
+            ]
+        };
+        for sql in statements {
+            sqlx::query(&sql).execute(pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Lists the history of the model in descending order of the creation time.
+    async fn list_history(primary_key: &Self::PrimaryKey, limit: usize) -> Result<Vec<Map>, Error> {
+        let pool = Self::acquire_reader().await?.pool();
+        let history_table_name = Self::history_table_name();
+        let model_id = Query::escape_string(primary_key);
+        let sql = format!(
+            "SELECT * FROM {history_table_name} WHERE model_id = {model_id} \
+                ORDER BY created_at DESC LIMIT {limit};"
+        );
+
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
+        let rows = sqlx::query(&sql).fetch_all(pool).await?;
+        let mut data = Vec::with_capacity(rows.len());
+        for row in rows.iter() {
+            data.push(decode_history(Map::decode_row(row)?));
+        }
+        ctx.set_query(&sql);
+        ctx.set_query_result(Some(u64::try_from(data.len())?), true);
+        Self::after_scan(&ctx).await?;
+        Self::after_query(&ctx).await?;
+        Ok(data)
+    }
+
+    /// Restores the model to the version recorded in the history entry.
+    /// The model is inserted again if it has been deleted from the table,
+    /// which is rejected if it has a write-only column without a default value
+    /// since the write-only columns are not recorded in the history.
+    async fn restore_version(
+        primary_key: &Self::PrimaryKey,
+        history_id: &Uuid,
+    ) -> Result<QueryContext, Error> {
+        let pool = Self::acquire_writer().await?.pool();
+        let history_table_name = Self::history_table_name();
+        let model_id = Query::escape_string(primary_key);
+        let history_id = Query::escape_string(history_id);
+        let sql = format!(
+            "SELECT * FROM {history_table_name} \
+                WHERE id = {history_id} AND model_id = {model_id};"
+        );
+        let mut history = match sqlx::query(&sql).fetch_optional(pool).await? {
+            Some(row) => decode_history(Map::decode_row(&row)?),
+            None => {
+                return Err(Error::new(format!(
+                    "404 Not Found: cannot find the history `{history_id}` of `{model_id}`"
+                )));
+            }
+        };
+        let Some(JsonValue::Object(mut data)) = history.remove("data") else {
+            return Err(Error::new(format!(
+                "404 Not Found: the history `{history_id}` has no model data"
+            )));
+        };
+
+        let entry = AuditEntry::capture_by_id::<Self>(primary_key.to_string()).await?;
+        let ctx = if entry.snapshots.iter().any(|(_, before)| before.is_some()) {
+            data.remove(Self::PRIMARY_KEY_NAME);
+            if Self::has_column("version") {
+                data.upsert("version", Map::from_entry("$inc", 1));
+            }
+
+            let mut query = Query::default();
+            query.add_filter(Self::PRIMARY_KEY_NAME, primary_key.to_string());
+            let mut mutation = Mutation::new(data);
+            Self::update_one(&query, &mut mutation).await?
+        } else {
+            if let Some(field) = Self::writeonly_fields().iter().find(|field| {
+                Self::get_column(field)
+                    .is_some_and(|col| col.is_not_null() && col.default_value().is_none())
+            }) {
+                return Err(Error::new(format!(
+                    "409 Conflict: the deleted model can not be restored \
+                        without the write-only column `{field}`"
+                )));
+            }
+
+            let sql = schema::format_insert_statement::<Self>(data)?;
+            let mut ctx = Self::before_scan(&sql).await?;
+            ctx.set_model_name(Self::model_name());
+            let query_result = sqlx::query(&sql).execute(pool).await?;
+            Self::invalidate_query_cache();
+            let rows_affected = query_result.rows_affected();
+            ctx.set_query(sql);
+            ctx.set_query_result(Some(rows_affected), rows_affected == 1);
+            Self::after_scan(&ctx).await?;
+            ctx
+        };
+        entry.record::<Self>(&ctx, AuditAction::Restore).await?;
+        Ok(ctx)
+    }
+}
+
+impl<M: Schema> AuditTrail for M {}
+
+/// Decodes the history entry in which the JSON values may be stored as strings.
+fn decode_history(mut history: Map) -> Map {
+    for key in ["changes", "data"] {
+        if let Some(JsonValue::String(value)) = history.get(key)
+            && let Ok(value) = serde_json::from_str::<JsonValue>(value)
+        {
+            history.upsert(key, value);
+        }
+    }
+    history
+}
+
+/// Computes the changes of the fields between two snapshots,
+/// in which each change has the format `{ "before": .., "after": .. }`.
+fn diff_snapshots(before: Option<&Map>, after: Option<&Map>) -> Map {
+    let empty = Map::new();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+    let mut changes = Map::new();
+    for (key, value) in after {
+        let prev_value = before.get(key).cloned().unwrap_or_default();
+        if &prev_value != value {
+            let mut change = Map::from_entry("before", prev_value);
+            change.upsert("after", value.clone());
+            changes.upsert(key, change);
+        }
+    }
+    for (key, value) in before {
+        if !after.contains_key(key) {
+            let mut change = Map::from_entry("before", value.clone());
+            change.upsert("after", JsonValue::Null);
+            changes.upsert(key, change);
+        }
+    }
+    changes
+}
+
+thread_local! {
+    /// Audit context of the current scope.
+    static CURRENT_AUDIT_CONTEXT: RefCell<Option<AuditContext>> = const { RefCell::new(None) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_snapshots;
+    use crate::{extension::JsonObjectExt, Map};
+
+    #[test]
+    fn it_diffs_snapshots() {
+        let mut before = Map::from_entry("name", "alice");
+        before.upsert("status", "Active");
+        let mut after = Map::from_entry("name", "alice");
+        after.upsert("status", "Inactive");
+
+        let changes = diff_snapshots(Some(&before), Some(&after));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes.get_object("status").unwrap().get_str("after"),
+            Some("Inactive")
+        );
+
+        let changes = diff_snapshots(None, Some(&after));
+        assert_eq!(changes.len(), 2);
+
+        let changes = diff_snapshots(Some(&before), None);
+        assert!(changes.get_object("name").unwrap()["after"].is_null());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{query::QueryExt, Schema};
+use crate::{
+    error::Error,
+    model::{Join, JoinType, Query, QueryBuilder},
+    Map,
+};
+use serde::de::DeserializeOwned;
+
+impl QueryBuilder {
+    /// Adds an inner join from the model `L` to the model `R`
+    /// on the pairs of the left and right columns.
+    #[inline]
+    pub fn inner_join<L: Schema, R: Schema>(self, on: &[(&str, &str)]) -> Self {
+        self.join_with::<L, R>(JoinType::Inner, on, &Query::default())
+    }
+
+    /// Adds a left outer join from the model `L` to the model `R`
+    /// on the pairs of the left and right columns.
+    #[inline]
+    pub fn left_join<L: Schema, R: Schema>(self, on: &[(&str, &str)]) -> Self {
+        self.join_with::<L, R>(JoinType::Left, on, &Query::default())
+    }
+
+    /// Adds a right outer join from the model `L` to the model `R`
+    /// on the pairs of the left and right columns.
+    #[inline]
+    pub fn right_join<L: Schema, R: Schema>(self, on: &[(&str, &str)]) -> Self {
+        self.join_with::<L, R>(JoinType::Right, on, &Query::default())
+    }
+
+    /// Adds a join from the model `L` to the model `R` on the pairs of the left and right columns.
+    /// The projection fields and filters of the query are applied to the joined table.
+    pub fn join_with<L: Schema, R: Schema>(
+        self,
+        join_type: JoinType,
+        on: &[(&str, &str)],
+        query: &Query,
+    ) -> Self {
+        let left_model_name = L::model_name();
+        let right_model_name = R::model_name();
+        let conditions = on
+            .iter()
+            .map(|(left_col, right_col)| {
+                let left_col = format!("{left_model_name}.{left_col}");
+                let right_col = format!("{right_model_name}.{right_col}");
+                let left_col_field = Query::format_field(&left_col);
+                let right_col_field = Query::format_field(&right_col);
+                format!("{left_col_field} = {right_col_field}")
+            })
+            .collect::<Vec<_>>();
+
+        let table_name = query.format_table_name::<R>();
+        let projection = format_aliased_fields::<R>(query);
+        let mut join = Join::new(join_type, table_name, conditions.join(" AND "), projection);
+        let filters = qualify_filters::<R>(&query.selection_filters::<R>());
+        join.set_filters(filters, Query::format_filters_with::<R>);
+        self.join(join)
+    }
+}
+
+/// Decodes a row of the join query as a tuple of models.
+/// The models on the nullable side of an outer join should be decoded as `Option<M>`.
+pub trait DecodeJoin: Sized {
+    /// Decodes the row data in which the fields are aliased as `{model_name}__{field}`.
+    async fn decode_join(data: Map) -> Result<Self, Error>;
+}
+
+/// A model in the row of the join query, which is either `M` or `Option<M>`.
+pub trait JoinedModel: Sized {
+    /// Decodes the model from the fields with the prefix of the model name.
+    async fn decode_joined(data: &Map) -> Result<Self, Error>;
+}
+
+impl<M: Schema + DeserializeOwned> JoinedModel for M {
+    async fn decode_joined(data: &Map) -> Result<Self, Error> {
+        if let Some(model) = decode_model::<M>(data).await? {
+            Ok(model)
+        } else {
+            let model_name = M::model_name();
+            let message = format!(
+                "the model `{model_name}` is not matched in the outer join, \
+                    which should be decoded as an `Option`"
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+impl<M: Schema + DeserializeOwned> JoinedModel for Option<M> {
+    #[inline]
+    async fn decode_joined(data: &Map) -> Result<Self, Error> {
+        decode_model::<M>(data).await
+    }
+}
+
+macro_rules! impl_decode_join {
+    ($($model:ident),+) => {
+        impl<$($model: JoinedModel),+> DecodeJoin for ($($model,)+) {
+            async fn decode_join(data: Map) -> Result<Self, Error> {
+                Ok(($($model::decode_joined(&data).await?,)+))
+            }
+        }
+    };
+}
+
+impl_decode_join!(A, B);
+impl_decode_join!(A, B, C);
+impl_decode_join!(A, B, C, D);
+
+/// Decodes the model from the fields with the prefix of the model name.
+/// It returns `None` for the unmatched rows in outer joins, in which all the fields are `NULL`.
+async fn decode_model<M: Schema + DeserializeOwned>(data: &Map) -> Result<Option<M>, Error> {
+    let prefix = format!("{}__", M::model_name());
+    let mut map = data
+        .iter()
+        .filter_map(|(key, value)| {
+            let field = key.strip_prefix(&prefix)?;
+            (!value.is_null()).then(|| (field.to_owned(), value.clone()))
+        })
+        .collect::<Map>();
+    if map.is_empty() {
+        return Ok(None);
+    }
+    M::after_decode(&mut map).await?;
+    serde_json::from_value(map.into())
+        .map(Some)
+        .map_err(Error::from)
+}
+
+/// Formats the projection fields of the model aliased as `{model_name}__{field}`.
+pub(super) fn format_aliased_fields<M: Schema>(query: &Query) -> String {
+    let model_name = M::model_name();
+    let fields = if query.fields().is_empty() {
+        M::fields().iter().map(|&field| field.to_owned()).collect()
+    } else {
+        query.fields().to_vec()
+    };
+    let fields = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains(':') {
+                field
+            } else {
+                let expr = Query::format_field(&format!("{model_name}.{field}")).into_owned();
+                format!("{model_name}__{field}:{expr}")
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut query = Query::default();
+    query.allow_fields(&fields.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+    query.format_table_fields::<M>().into_owned()
+}
+
+/// Qualifies the filters on the columns of the model by the model name.
+pub(super) fn qualify_filters<M: Schema>(filters: &Map) -> Map {
+    let model_name = M::model_name();
+    filters
+        .iter()
+        .map(|(key, value)| {
+            let key = if M::has_column(key) {
+                format!("{model_name}.{key}")
+            } else {
+                key.to_owned()
+            };
+            (key, value.clone())
+        })
+        .collect()
+}
@@ -0,0 +1,240 @@
+use super::{query::QueryExt, DatabaseDriver};
+use crate::{
+    datetime::DateTime,
+    error::Error,
+    model::{Column, EncodeColumn, Query},
+};
+use sqlx::{Executor, Pool, Row};
+use std::sync::LazyLock;
+
+/// Name of the table which records the applied migrations.
+static MIGRATIONS_TABLE_NAME: LazyLock<&'static str> = LazyLock::new(|| {
+    [*super::NAMESPACE_PREFIX, "schema_migrations"]
+        .join("_")
+        .leak()
+});
+
+/// Fetches the column names and types of a live table.
+pub(super) async fn fetch_table_columns(
+    pool: &Pool<DatabaseDriver>,
+    table_name: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let sql = if cfg!(feature = "orm-mysql") {
+        format!(
+            "SELECT CAST(column_name AS CHAR) AS name, CAST(column_type AS CHAR) AS type \
+                FROM information_schema.columns \
+                WHERE table_schema = DATABASE() AND table_name = '{table_name}';"
+        )
+    } else if cfg!(feature = "orm-postgres") {
+        let (table_schema, table_name) = split_table_name(table_name);
+        format!(
+            "SELECT column_name::TEXT AS name, udt_name::TEXT AS type \
+                FROM information_schema.columns \
+                WHERE table_schema = {table_schema} AND table_name = '{table_name}';"
+        )
+    } else {
+        format!("SELECT name, type FROM pragma_table_info('{table_name}');")
+    };
+    let mut columns = Vec::new();
+    for row in sqlx::query(&sql).fetch_all(pool).await? {
+        let name = row.try_get::<String, _>("name")?;
+        let column_type = row.try_get::<String, _>("type")?;
+        columns.push((name, column_type));
+    }
+    Ok(columns)
+}
+
+/// Fetches the index names of a live table.
+pub(super) async fn fetch_table_indexes(
+    pool: &Pool<DatabaseDriver>,
+    table_name: &str,
+) -> Result<Vec<String>, Error> {
+    let sql = if cfg!(feature = "orm-mysql") {
+        format!(
+            "SELECT DISTINCT CAST(index_name AS CHAR) AS name \
+                FROM information_schema.statistics \
+                WHERE table_schema = DATABASE() AND table_name = '{table_name}';"
+        )
+    } else if cfg!(feature = "orm-postgres") {
+        let (table_schema, table_name) = split_table_name(table_name);
+        format!(
+            "SELECT indexname::TEXT AS name FROM pg_indexes \
+                WHERE schemaname = {table_schema} AND tablename = '{table_name}';"
+        )
+    } else {
+        format!("SELECT name FROM pragma_index_list('{table_name}');")
+    };
+    let mut indexes = Vec::new();
+    for row in sqlx::query(&sql).fetch_all(pool).await? {
+        indexes.push(row.try_get::<String, _>("name")?);
+    }
+    Ok(indexes)
+}
+
+/// Splits the table name qualified by a schema into the schema expression and the table name.
+fn split_table_name(table_name: &str) -> (String, &str) {
+    match table_name.split_once('.') {
+        Some((table_schema, table_name)) => (format!("'{table_schema}'"), table_name),
+        None => ("current_schema()".to_owned(), table_name),
+    }
+}
+
+/// Formats a statement to change the column type.
+/// Returns `None` if the backend can not alter the column type in place.
+pub(super) fn format_alter_column_type(
+    table_name: &str,
+    column_name: &str,
+    column_type: &str,
+) -> Option<String> {
+    if cfg!(feature = "orm-mysql") {
+        let sql = format!("ALTER TABLE {table_name} MODIFY COLUMN {column_name} {column_type};");
+        Some(sql)
+    } else if cfg!(feature = "orm-postgres") {
+        let column_type = cast_column_type(column_type);
+        let sql = format!(
+            "ALTER TABLE {table_name} ALTER COLUMN {column_name} \
+                TYPE {column_type} USING {column_name}::{column_type};"
+        );
+        Some(sql)
+    } else {
+        None
+    }
+}
+
+/// Formats a statement to set the default value of a column, or to drop it if `None`.
+pub(super) fn format_alter_column_default(
+    table_name: &str,
+    column_name: &str,
+    default_value: Option<&str>,
+) -> String {
+    if let Some(value) = default_value {
+        format!("ALTER TABLE {table_name} ALTER COLUMN {column_name} SET DEFAULT {value};")
+    } else {
+        format!("ALTER TABLE {table_name} ALTER COLUMN {column_name} DROP DEFAULT;")
+    }
+}
+
+/// Maps the pseudo types of auto-increment columns to the types which can be used in casts.
+pub(super) fn cast_column_type(column_type: &str) -> &str {
+    match column_type {
+        "BIGSERIAL" => "BIGINT",
+        "SERIAL" => "INT",
+        "SMALLSERIAL" => "SMALLINT",
+        _ => column_type,
+    }
+}
+
+/// Returns `true` if the live column type matches the type of the model column.
+pub(super) fn is_same_column_type(expected: &str, actual: &str) -> bool {
+    normalize_column_type(expected) == normalize_column_type(actual)
+}
+
+/// Normalizes the column type reported by the database
+/// so that the synonyms can be compared with each other.
+fn normalize_column_type(column_type: &str) -> String {
+    let column_type = column_type.trim().to_ascii_uppercase();
+    if column_type == "TINYINT(1)" {
+        return "BOOLEAN".to_owned();
+    }
+
+    // Strips the display width of integer types in MySQL, e.g. `BIGINT(20) UNSIGNED`.
+    let column_type = match column_type.split_once('(') {
+        Some((name, modifier)) if name.ends_with("INT") => {
+            let suffix = modifier.split_once(')').map(|s| s.1).unwrap_or_default();
+            format!("{name}{suffix}")
+        }
+        _ => column_type,
+    };
+    let column_type = match column_type.as_str() {
+        "BOOL" => "BOOLEAN",
+        "INT8" | "BIGSERIAL" => "BIGINT",
+        "INT4" | "INTEGER" | "SERIAL" => "INT",
+        "INT2" | "SMALLSERIAL" => "SMALLINT",
+        "FLOAT8" => "DOUBLE PRECISION",
+        "FLOAT4" => "REAL",
+        "DECIMAL" | "DECIMAL(10,0)" => "NUMERIC",
+        "TIMESTAMP WITH TIME ZONE" => "TIMESTAMPTZ",
+        "TIMESTAMP WITHOUT TIME ZONE" => "TIMESTAMP",
+        "_TEXT" => "TEXT[]",
+        "_UUID" => "UUID[]",
+        _ => return column_type,
+    };
+    column_type.to_owned()
+}
+
+/// Creates the table which records the applied migrations.
+pub(super) async fn create_migrations_table(pool: &Pool<DatabaseDriver>) -> Result<(), Error> {
+    let table_name = *MIGRATIONS_TABLE_NAME;
+    let applied_at = Column::new("applied_at", "DateTime", true);
+    let applied_at_type = applied_at.column_type();
+    let sql = format!(
+        "CREATE TABLE IF NOT EXISTS {table_name} (\n  \
+            version BIGINT NOT NULL,\n  \
+            table_name VARCHAR(255) NOT NULL,\n  \
+            statements TEXT NOT NULL,\n  \
+            applied_at {applied_at_type} NOT NULL,\n  \
+            PRIMARY KEY (table_name, version)\n\
+        );"
+    );
+    sqlx::query(&sql).execute(pool).await?;
+    Ok(())
+}
+
+/// Records the statements applied to a table as a new migration version.
+pub(super) async fn record_migration<'c, E>(
+    executor: E,
+    table_name: &str,
+    statements: &[String],
+) -> Result<i64, Error>
+where
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let migrations_table_name = *MIGRATIONS_TABLE_NAME;
+    let version = DateTime::now().timestamp_micros();
+    let applied_at = Column::new("applied_at", "DateTime", true);
+    let applied_at = applied_at.format_value("now");
+    let table_name = Query::escape_string(table_name);
+    let statements = Query::escape_string(statements.join("\n"));
+    let sql = format!(
+        "INSERT INTO {migrations_table_name} (version, table_name, statements, applied_at) \
+            VALUES ({version}, {table_name}, {statements}, {applied_at});"
+    );
+    sqlx::query(&sql).execute(executor).await?;
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_same_column_type, split_table_name};
+
+    #[test]
+    fn it_compares_column_types() {
+        let same_types = [
+            ("BIGSERIAL", "int8"),
+            ("INT", "int4"),
+            ("TIMESTAMPTZ", "timestamptz"),
+            ("TEXT[]", "_text"),
+            ("BOOLEAN", "tinyint(1)"),
+            ("BIGINT UNSIGNED", "bigint(20) unsigned"),
+            ("VARCHAR(255)", "varchar(255)"),
+            ("NUMERIC", "decimal(10,0)"),
+            ("INTEGER", "INTEGER"),
+        ];
+        for (column_type, live_column_type) in same_types {
+            assert!(is_same_column_type(column_type, live_column_type));
+        }
+        assert!(!is_same_column_type("TEXT", "VARCHAR(255)"));
+        assert!(!is_same_column_type("BIGINT", "int4"));
+    }
+
+    #[test]
+    fn it_splits_table_names() {
+        let (table_schema, table_name) = split_table_name("acme.zino_user");
+        assert_eq!(table_schema, "'acme'");
+        assert_eq!(table_name, "zino_user");
+
+        let (table_schema, table_name) = split_table_name("zino_user");
+        assert_eq!(table_schema, "current_schema()");
+        assert_eq!(table_name, "zino_user");
+    }
+}
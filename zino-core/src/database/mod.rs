@@ -12,30 +12,49 @@
 
 use crate::{extension::TomlTableExt, state::State};
 use convert_case::{Case, Casing};
+use futures::future::{self, Either};
+use futures_timer::Delay;
 use sqlx::{
     pool::{Pool, PoolOptions},
-    Connection,
+    ConnectOptions, Connection,
 };
 use std::{
+    pin::pin,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
-        LazyLock,
+        Arc, LazyLock,
     },
     time::Duration,
 };
 use toml::value::Table;
 
 mod accessor;
+mod aggregation;
+mod audit;
 mod decode;
 mod helper;
+mod join;
+mod migration;
 mod mutation;
 mod query;
+mod query_stats;
 mod schema;
+mod scope;
+mod tenant;
+mod transaction;
+
+#[cfg(feature = "cache")]
+mod query_cache;
 
 pub use accessor::ModelAccessor;
+pub use audit::{AuditAction, AuditContext, AuditEntry, AuditTrail};
 pub use decode::decode;
 pub use helper::ModelHelper;
+pub use join::{DecodeJoin, JoinedModel};
+pub use query_stats::QueryStats;
 pub use schema::Schema;
+pub use tenant::TenantContext;
+pub use transaction::TransactionContext;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "orm-mysql")] {
@@ -122,7 +141,9 @@ cfg_if::cfg_if! {
 
         /// Options and flags which can be used to configure a SQLite connection.
         fn new_connect_options(database: &'static str, config: &'static Table) -> SqliteConnectOptions {
-            let mut connect_options = SqliteConnectOptions::new().create_if_missing(true);
+            let mut connect_options = SqliteConnectOptions::new()
+                .create_if_missing(true)
+                .foreign_keys(true);
             if let Some(read_only) = config.get_bool("read_only") {
                 connect_options = connect_options.read_only(read_only);
             }
@@ -147,8 +168,10 @@ pub struct ConnectionPool {
     database: &'static str,
     /// Pool.
     pool: Pool<DatabaseDriver>,
+    /// A flag which indicates whether the pool is a read replica.
+    replica: bool,
     /// Availability.
-    available: AtomicBool,
+    available: Arc<AtomicBool>,
 }
 
 impl ConnectionPool {
@@ -164,6 +187,44 @@ impl ConnectionPool {
         self.available.store(available, Relaxed);
     }
 
+    /// Returns `true` if the connection pool is a read replica,
+    /// which is configured by `role = "replica"` in a group of pools with the same name.
+    #[inline]
+    pub fn is_replica(&self) -> bool {
+        self.replica
+    }
+
+    /// Checks the availability of the connection pool by pinging the database,
+    /// and stores the result into the availability.
+    /// The ping runs on a dedicated connection with the connect options of the pool,
+    /// so that it is not affected by the pool being exhausted.
+    /// The pool is unavailable if the ping does not finish within the `health-check-timeout`.
+    pub async fn check_availability(&self) -> bool {
+        let ping = async {
+            match self.pool.connect_options().connect().await {
+                Ok(mut conn) => {
+                    let available = conn.ping().await.is_ok();
+                    if let Err(err) = conn.close().await {
+                        tracing::warn!("fail to close the connection: {err}");
+                    }
+                    available
+                }
+                Err(_) => false,
+            }
+        };
+        let timeout = Delay::new(health_check_timeout());
+        let available = match future::select(pin!(ping), timeout).await {
+            Either::Left((available, _)) => available,
+            Either::Right(_) => false,
+        };
+        if available != self.is_available() {
+            let name = self.name;
+            tracing::warn!(name, available, "availability of the connection pool is changed");
+        }
+        self.store_availability(available);
+        available
+    }
+
     /// Returns the name.
     #[inline]
     pub fn name(&self) -> &'static str {
@@ -185,6 +246,7 @@ impl ConnectionPool {
     /// Connects lazily to the database according to the config.
     pub fn connect_lazy(config: &'static Table) -> Self {
         let name = config.get_str("name").unwrap_or("main");
+        let replica = config.get_str("role").is_some_and(|role| role == "replica");
 
         // Connect options.
         let database = config
@@ -208,6 +270,8 @@ impl ConnectionPool {
             .get_duration("acquire-timeout")
             .unwrap_or_else(|| Duration::from_secs(30));
         let health_check_interval = config.get_u64("health-check-interval").unwrap_or(60);
+        let available = Arc::new(AtomicBool::new(true));
+        let pool_available = available.clone();
         let pool = PoolOptions::<DatabaseDriver>::new()
             .max_connections(max_connections)
             .min_connections(min_connections)
@@ -216,15 +280,14 @@ impl ConnectionPool {
             .acquire_timeout(acquire_timeout)
             .test_before_acquire(false)
             .before_acquire(move |conn, meta| {
+                let available = pool_available.clone();
                 Box::pin(async move {
-                    if meta.idle_for.as_secs() > health_check_interval
-                        && let Some(cp) = SHARED_CONNECTION_POOLS.get_pool(name)
-                    {
+                    if meta.idle_for.as_secs() > health_check_interval {
                         if let Err(err) = conn.ping().await {
-                            cp.store_availability(false);
+                            available.store(false, Relaxed);
                             return Err(err);
                         } else {
-                            cp.store_availability(cp.pool().num_idle() > 0);
+                            available.store(true, Relaxed);
                         }
                     }
                     Ok(true)
@@ -236,7 +299,8 @@ impl ConnectionPool {
             name,
             database,
             pool,
-            available: AtomicBool::new(true),
+            replica,
+            available,
         }
     }
 }
@@ -246,18 +310,69 @@ impl ConnectionPool {
 struct ConnectionPools(Vec<ConnectionPool>);
 
 impl ConnectionPools {
-    /// Returns a connection pool with the specific name.
+    /// Returns a primary connection pool with the specific name.
+    /// The available pools take precedence, and a read replica is never returned.
     pub(crate) fn get_pool(&self, name: &str) -> Option<&ConnectionPool> {
         let mut pool = None;
-        for cp in self.0.iter().filter(|cp| cp.name() == name) {
+        for cp in self.0.iter().filter(|cp| cp.name() == name && !cp.is_replica()) {
             if cp.is_available() {
                 return Some(cp);
-            } else {
-                pool = Some(cp);
             }
+            pool.get_or_insert(cp);
         }
         pool
     }
+
+    /// Returns a connection pool with the specific name for reads.
+    /// The reads are load-balanced across the available read replicas,
+    /// and fall back to the primary pool if there are no available replicas.
+    pub(crate) fn get_reader(&self, name: &str) -> Option<&ConnectionPool> {
+        let replicas = self
+            .0
+            .iter()
+            .filter(|cp| cp.name() == name && cp.is_replica())
+            .collect::<Vec<_>>();
+        let available_replicas = replicas
+            .iter()
+            .copied()
+            .filter(|cp| cp.is_available())
+            .collect::<Vec<_>>();
+        if available_replicas.is_empty() {
+            self.get_pool(name).or_else(|| replicas.first().copied())
+        } else {
+            let index = REPLICA_INDEX.fetch_add(1, Relaxed) % available_replicas.len();
+            Some(available_replicas[index])
+        }
+    }
+}
+
+/// Checks the availability of all the connection pools concurrently.
+/// It is executed periodically by the application so that the failed pools can be recovered.
+pub async fn check_connection_pools() {
+    let checks = SHARED_CONNECTION_POOLS
+        .0
+        .iter()
+        .map(|cp| cp.check_availability());
+    future::join_all(checks).await;
+}
+
+/// Returns the interval of checking the availability of the connection pools,
+/// which is configured by the `health-check-interval` field of the `database` table
+/// in seconds and defaults to 60 seconds.
+pub fn health_check_interval() -> Duration {
+    let interval = State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_u64("health-check-interval"))
+        .unwrap_or(60);
+    Duration::from_secs(interval)
+}
+
+/// Returns the timeout of pinging a connection pool in the health check.
+fn health_check_timeout() -> Duration {
+    State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_duration("health-check-timeout"))
+        .unwrap_or(Duration::from_secs(1))
 }
 
 /// Shared connection pools.
@@ -305,3 +420,6 @@ static NAMESPACE_PREFIX: LazyLock<&'static str> = LazyLock::new(|| {
 
 /// Max number of returning rows.
 static MAX_ROWS: AtomicUsize = AtomicUsize::new(10000);
+
+/// Index of the read replica for the round-robin load balancing.
+static REPLICA_INDEX: AtomicUsize = AtomicUsize::new(0);
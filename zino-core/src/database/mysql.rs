@@ -231,15 +231,18 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     format!(r#"{field} IS NOT TRUE"#)
                 }
             }
-            "u64" | "i64" | "u32" | "i32" | "Option<u64>" | "Option<i64>" | "Option<u32>"
-            | "Option<i32>" => {
+            _ if self.is_numeric() => {
                 if let Some(value) = value.as_str() {
                     if value == "null" {
                         format!(r#"{field} IS NULL"#)
                     } else if value == "notnull" {
                         format!(r#"{field} IS NOT NULL"#)
                     } else if value.contains(',') {
-                        let value = value.split(',').collect::<Vec<_>>().join(",");
+                        let value = value
+                            .split(',')
+                            .map(|s| self.format_value(s))
+                            .collect::<Vec<_>>()
+                            .join(",");
                         format!(r#"{field} IN ({value})"#)
                     } else {
                         let value = self.format_value(value);
@@ -287,7 +290,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     format!(r#"{field} = {value}"#)
                 }
             }
-            "DateTime" | "Date" | "Time" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" => {
+            _ if self.is_datetime() => {
                 if let Some(value) = value.as_str() {
                     if let Some((min_value, max_value)) = value.split_once(',') {
                         let min_value = self.format_value(min_value);
@@ -530,6 +533,16 @@ impl QueryExt<DatabaseDriver> for Query {
         self.limit()
     }
 
+    #[inline]
+    fn query_cursor(&self) -> Option<&Map> {
+        self.cursor()
+    }
+
+    #[inline]
+    fn query_show_deleted(&self) -> bool {
+        self.show_deleted()
+    }
+
     #[inline]
     fn placeholder(_n: usize) -> SharedString {
         "?".into()
@@ -1,4 +1,4 @@
-use super::{query::QueryExt, DatabaseDriver, DatabaseRow, Schema};
+use super::{query::QueryExt, tenant, DatabaseDriver, DatabaseRow, Schema};
 use crate::{
     datetime::DateTime,
     error::Error,
@@ -7,11 +7,15 @@ use crate::{
     AvroValue, JsonValue, Map, Record, SharedString, Uuid,
 };
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
-use sqlx::{types::Decimal, Column as _, Row, TypeInfo, ValueRef};
+use sqlx::{postgres::PgTypeKind, types::Decimal, Column as _, Row, TypeInfo, ValueRef};
 use std::borrow::Cow;
 
 impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
     fn column_type(&self) -> &str {
+        if let Some(enum_type) = self.enum_type() {
+            return enum_type;
+        }
+
         let type_name = self.type_name();
         match type_name {
             "bool" => "BOOLEAN",
@@ -217,6 +221,13 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         let value = self.encode_value(Some(value));
                         let condition = format!(r#"array_length({field}, 1) = {value}"#);
                         conditions.push(condition);
+                    } else if self.enum_type().is_some()
+                        && matches!(operator, "LIKE" | "ILIKE" | "~*")
+                    {
+                        // Enum types have no pattern matching operators.
+                        let value = self.encode_value(Some(value));
+                        let condition = format!(r#"{field}::text {operator} {value}"#);
+                        conditions.push(condition);
                     } else {
                         let value = self.encode_value(Some(value));
                         let condition = format!(r#"{field} {operator} {value}"#);
@@ -244,15 +255,18 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     format!(r#"{field} IS NOT TRUE"#)
                 }
             }
-            "u64" | "i64" | "u32" | "i32" | "Option<u64>" | "Option<i64>" | "Option<u32>"
-            | "Option<i32>" => {
+            _ if self.is_numeric() => {
                 if let Some(value) = value.as_str() {
                     if value == "null" {
                         format!(r#"{field} IS NULL"#)
                     } else if value == "notnull" {
                         format!(r#"{field} IS NOT NULL"#)
                     } else if value.contains(',') {
-                        let value = value.split(',').collect::<Vec<_>>().join(",");
+                        let value = value
+                            .split(',')
+                            .map(|s| self.format_value(s))
+                            .collect::<Vec<_>>()
+                            .join(",");
                         format!(r#"{field} IN ({value})"#)
                     } else {
                         let value = self.format_value(value);
@@ -296,7 +310,11 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         if index > 0 {
                             let (operator, value) = value.split_at(index);
                             let value = Query::escape_string(value);
-                            format!(r#"{field} {operator} {value}"#)
+                            if self.enum_type().is_some() {
+                                format!(r#"{field}::text {operator} {value}"#)
+                            } else {
+                                format!(r#"{field} {operator} {value}"#)
+                            }
                         } else {
                             let value = Query::escape_string(value);
                             format!(r#"{field} = {value}"#)
@@ -307,7 +325,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                     format!(r#"{field} = {value}"#)
                 }
             }
-            "DateTime" | "Date" | "Time" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" => {
+            _ if self.is_datetime() => {
                 if let Some(value) = value.as_str() {
                     if let Some((min_value, max_value)) = value.split_once(',') {
                         let min_value = self.format_value(min_value);
@@ -435,7 +453,13 @@ impl DecodeRow<DatabaseRow> for Map {
                             .into()
                     }
                     "JSONB" | "JSON" => decode_column::<JsonValue>(field, raw_value)?,
-                    _ => JsonValue::Null,
+                    _ => {
+                        if let PgTypeKind::Enum(_) = col.type_info().kind() {
+                            decode_column::<String>(field, raw_value)?.into()
+                        } else {
+                            JsonValue::Null
+                        }
+                    }
                 }
             };
             if !value.is_ignorable() {
@@ -513,7 +537,20 @@ impl DecodeRow<DatabaseRow> for Record {
                         AvroValue::Array(vec)
                     }
                     "JSONB" | "JSON" => decode_column::<JsonValue>(field, raw_value)?.into(),
-                    _ => AvroValue::Null,
+                    _ => {
+                        if let PgTypeKind::Enum(variants) = col.type_info().kind() {
+                            let value = decode_column::<String>(field, raw_value)?;
+                            let Some(index) = variants.iter().position(|v| v == &value) else {
+                                let message = format!(
+                                    "invalid value `{value}` for the enum column `{field}`"
+                                );
+                                return Err(Error::new(message));
+                            };
+                            AvroValue::Enum(u32::try_from(index)?, value)
+                        } else {
+                            AvroValue::Null
+                        }
+                    }
                 }
             };
             record.push((field.to_owned(), value));
@@ -553,6 +590,16 @@ impl QueryExt<DatabaseDriver> for Query {
         self.limit()
     }
 
+    #[inline]
+    fn query_cursor(&self) -> Option<&Map> {
+        self.cursor()
+    }
+
+    #[inline]
+    fn query_show_deleted(&self) -> bool {
+        self.show_deleted()
+    }
+
     #[inline]
     fn placeholder(n: usize) -> SharedString {
         if n == 1 {
@@ -615,7 +662,11 @@ impl QueryExt<DatabaseDriver> for Query {
     fn format_table_name<M: Schema>(&self) -> String {
         let table_name = M::table_name();
         let model_name = M::model_name();
-        format!(r#""{table_name}" "{model_name}""#)
+        if let Some(schema_name) = tenant::current_schema_name() {
+            format!(r#""{schema_name}"."{table_name}" "{model_name}""#)
+        } else {
+            format!(r#""{table_name}" "{model_name}""#)
+        }
     }
 
     fn parse_text_search(filter: &Map) -> Option<String> {
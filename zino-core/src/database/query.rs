@@ -1,9 +1,10 @@
-use super::Schema;
+use super::{migration, schema, tenant, DatabaseDriver, Schema};
 use crate::{
     extension::{JsonObjectExt, JsonValueExt},
-    model::EncodeColumn,
+    model::{Column, EncodeColumn},
     JsonValue, Map, SharedString,
 };
+use sqlx::{database::HasArguments, query::Query as SqlxQuery};
 use std::{borrow::Cow, fmt::Display};
 
 /// Extension trait for [`Query`](crate::model::Query).
@@ -23,6 +24,12 @@ pub(super) trait QueryExt<DB> {
     /// Returns the query limit.
     fn query_limit(&self) -> usize;
 
+    /// Returns a reference to the cursor for the keyset pagination.
+    fn query_cursor(&self) -> Option<&Map>;
+
+    /// Returns `true` if the soft-deleted rows should be selected.
+    fn query_show_deleted(&self) -> bool;
+
     /// Returns a placeholder for the n-th parameter.
     fn placeholder(n: usize) -> SharedString;
 
@@ -73,8 +80,116 @@ pub(super) trait QueryExt<DB> {
     }
 
     /// Formats the query filters to generate SQL `WHERE` expression.
+    #[inline]
     fn format_filters<M: Schema>(&self) -> String {
+        let filters = tenant::tenant_filters::<M>(self.keyset_filters());
+        Self::format_filters_with::<M>(&filters, None)
+    }
+
+    /// Formats the query filters to generate SQL `WHERE` expression
+    /// in which the values are replaced by placeholders and collected as `arguments`.
+    fn format_bound_filters<M: Schema>(&self, arguments: &mut Vec<JsonValue>) -> String {
+        let filters = tenant::tenant_filters::<M>(self.keyset_filters());
+        Self::format_filters_with::<M>(&filters, Some(arguments))
+    }
+
+    /// Formats the query filters to generate SQL `WHERE` expression for selecting models.
+    fn format_selection_filters<M: Schema>(
+        &self,
+        arguments: Option<&mut Vec<JsonValue>>,
+    ) -> String {
+        Self::format_filters_with::<M>(&self.selection_filters::<M>(), arguments)
+    }
+
+    /// Returns the query filters for selecting models, which are isolated by the tenant.
+    /// The soft-deleted rows are excluded unless the `show_deleted` flag has been enabled
+    /// or the filters have a condition on the `status` or `deleted_at` column.
+    /// The `status` column is only used if it can hold the `Deleted` value.
+    fn selection_filters<M: Schema>(&self) -> Cow<'_, Map> {
+        let mut filters = tenant::tenant_filters::<M>(self.keyset_filters());
+        if !self.query_show_deleted() {
+            if schema::has_deleted_status::<M>() {
+                if !filters.contains_key("status") {
+                    let filter = Map::from_entry("$ne", "Deleted");
+                    filters.to_mut().upsert("status", filter);
+                }
+            } else if M::has_column("deleted_at") && !filters.contains_key("deleted_at") {
+                filters.to_mut().upsert("deleted_at", "null");
+            }
+        }
+        filters
+    }
+
+    /// Returns the query filters combined with the keyset condition of the cursor.
+    /// For the sort order `(a DESC, b ASC)`, the condition is
+    /// `a < $a OR (a = $a AND b > $b)`.
+    ///
+    /// Null values are sorted as the greatest ones in PostgreSQL
+    /// and as the smallest ones in MySQL and SQLite.
+    fn keyset_filters(&self) -> Cow<'_, Map> {
         let filters = self.query_filters();
+        let Some(cursor) = self.query_cursor() else {
+            return Cow::Borrowed(filters);
+        };
+
+        let mut keyset_conditions = Vec::new();
+        let mut equality_conditions = Vec::new();
+        for (field, descending) in self.query_order() {
+            let field = field.as_ref();
+            let Some(value) = cursor.get(field) else {
+                break;
+            };
+            let nulls_last = if cfg!(feature = "orm-postgres") {
+                !descending
+            } else {
+                *descending
+            };
+            let null_condition = Map::from_entry(field, Map::from_entry("$is", "null"));
+            let condition = if value.is_null() {
+                // There are no rows after the null value if the nulls are sorted last.
+                (!nulls_last).then(|| Map::from_entry("$not", vec![null_condition.clone()]))
+            } else {
+                let operator = if *descending { "$lt" } else { "$gt" };
+                let condition = Map::from_entry(field, Map::from_entry(operator, value.clone()));
+                if nulls_last {
+                    let conditions = vec![condition, null_condition.clone()];
+                    Some(Map::from_entry("$or", conditions))
+                } else {
+                    Some(condition)
+                }
+            };
+            if let Some(condition) = condition {
+                let mut conditions = equality_conditions.clone();
+                conditions.push(condition);
+                keyset_conditions.push(Map::from_entry("$and", conditions));
+            }
+            if value.is_null() {
+                equality_conditions.push(null_condition);
+            } else {
+                let condition = Map::from_entry("$eq", value.clone());
+                equality_conditions.push(Map::from_entry(field, condition));
+            }
+        }
+        if keyset_conditions.is_empty() {
+            return Cow::Borrowed(filters);
+        }
+
+        let mut filters = filters.clone();
+        let keyset_filter = JsonValue::from(Map::from_entry("$or", keyset_conditions));
+        if let Some(JsonValue::Array(conditions)) = filters.get_mut("$and") {
+            conditions.push(keyset_filter);
+        } else {
+            filters.upsert("$and", vec![keyset_filter]);
+        }
+        Cow::Owned(filters)
+    }
+
+    /// Formats the filters to generate SQL `WHERE` expression.
+    /// If the `arguments` is `Some`, the values will be bound as parameters.
+    fn format_filters_with<M: Schema>(
+        filters: &Map,
+        mut arguments: Option<&mut Vec<JsonValue>>,
+    ) -> String {
         if filters.is_empty() {
             return String::new();
         }
@@ -85,25 +200,33 @@ pub(super) trait QueryExt<DB> {
             match key.as_str() {
                 "$and" => {
                     if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                        let arguments = arguments.as_deref_mut();
+                        let condition =
+                            Self::format_logical_filters::<M>(filters, " AND ", arguments);
                         conditions.push(condition);
                     }
                 }
                 "$not" => {
                     if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                        let arguments = arguments.as_deref_mut();
+                        let condition =
+                            Self::format_logical_filters::<M>(filters, " AND ", arguments);
                         conditions.push(format!("(NOT {condition})"));
                     }
                 }
                 "$nor" => {
                     if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                        let arguments = arguments.as_deref_mut();
+                        let condition =
+                            Self::format_logical_filters::<M>(filters, " OR ", arguments);
                         conditions.push(format!("(NOT {condition})"));
                     }
                 }
                 "$or" => {
                     if let Some(filters) = value.as_array() {
-                        let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                        let arguments = arguments.as_deref_mut();
+                        let condition =
+                            Self::format_logical_filters::<M>(filters, " OR ", arguments);
                         conditions.push(condition);
                     }
                 }
@@ -128,8 +251,12 @@ pub(super) trait QueryExt<DB> {
                     }
                 }
                 _ => {
-                    if let Some(col) = M::get_column(key) {
-                        let condition = col.format_filter(key, value);
+                    if let Some(col) = get_column::<M>(key) {
+                        let condition = if let Some(arguments) = arguments.as_deref_mut() {
+                            Self::format_bound_filter(col, key, value, arguments)
+                        } else {
+                            col.format_filter(key, value)
+                        };
                         if !condition.is_empty() {
                             conditions.push(condition);
                         }
@@ -153,7 +280,7 @@ pub(super) trait QueryExt<DB> {
                 .join(", ");
             expression += &format!(" GROUP BY {groups}");
             if let Some(filters) = filters.get_array("$having") {
-                let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                let condition = Self::format_logical_filters::<M>(filters, " AND ", arguments);
                 expression += &format!(" HAVING {condition}");
             }
         }
@@ -161,7 +288,11 @@ pub(super) trait QueryExt<DB> {
     }
 
     // Formats the filters with a logic operator.
-    fn format_logical_filters<M: Schema>(filters: &[JsonValue], operator: &str) -> String {
+    fn format_logical_filters<M: Schema>(
+        filters: &[JsonValue],
+        operator: &str,
+        mut arguments: Option<&mut Vec<JsonValue>>,
+    ) -> String {
         let mut conditions = Vec::with_capacity(filters.len());
         for filter in filters {
             if let JsonValue::Object(filter) = filter {
@@ -169,31 +300,44 @@ pub(super) trait QueryExt<DB> {
                     match key.as_str() {
                         "$and" => {
                             if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                                let arguments = arguments.as_deref_mut();
+                                let condition =
+                                    Self::format_logical_filters::<M>(filters, " AND ", arguments);
                                 conditions.push(condition);
                             }
                         }
                         "$not" => {
                             if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " AND ");
+                                let arguments = arguments.as_deref_mut();
+                                let condition =
+                                    Self::format_logical_filters::<M>(filters, " AND ", arguments);
                                 conditions.push(format!("(NOT {condition})"));
                             }
                         }
                         "$nor" => {
                             if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                                let arguments = arguments.as_deref_mut();
+                                let condition =
+                                    Self::format_logical_filters::<M>(filters, " OR ", arguments);
                                 conditions.push(format!("(NOT {condition})"));
                             }
                         }
                         "$or" => {
                             if let Some(filters) = value.as_array() {
-                                let condition = Self::format_logical_filters::<M>(filters, " OR ");
+                                let arguments = arguments.as_deref_mut();
+                                let condition =
+                                    Self::format_logical_filters::<M>(filters, " OR ", arguments);
                                 conditions.push(condition);
                             }
                         }
                         _ => {
-                            if let Some(col) = M::get_column(key) {
-                                let condition = col.format_filter(key, value);
+                            if let Some(col) = get_column::<M>(key) {
+                                let condition = if let Some(arguments) = arguments.as_deref_mut()
+                                {
+                                    Self::format_bound_filter(col, key, value, arguments)
+                                } else {
+                                    col.format_filter(key, value)
+                                };
                                 if !condition.is_empty() {
                                     conditions.push(condition);
                                 }
@@ -215,6 +359,163 @@ pub(super) trait QueryExt<DB> {
         }
     }
 
+    /// Formats a column filter in which the values are bound as query arguments.
+    /// It falls back to [`EncodeColumn::format_filter()`] for the values
+    /// which have special meanings for the column.
+    fn format_bound_filter(
+        col: &Column<'_>,
+        key: &str,
+        value: &JsonValue,
+        arguments: &mut Vec<JsonValue>,
+    ) -> String {
+        let type_name = col.type_name();
+        let is_numeric = col.is_numeric();
+        let is_datetime = col.is_datetime();
+        let is_string = matches!(type_name, "String" | "Option<String>");
+        let is_bindable =
+            is_numeric || is_datetime || is_string || matches!(type_name, "Uuid" | "Option<Uuid>");
+        if !is_bindable || col.index_type() == Some("text") {
+            return col.format_filter(key, value);
+        }
+
+        // Values which can not be bound directly.
+        let is_special_value = |value: &JsonValue| {
+            if let Some(value) = value.as_str() {
+                value.is_empty()
+                    || value == "null"
+                    || value == "notnull"
+                    || (is_numeric && value.parse::<f64>().is_err())
+                    || (is_datetime && value.chars().all(|ch| ch.is_ascii_alphabetic()))
+                    || value.starts_with(|ch| "!~*".contains(ch))
+            } else {
+                !matches!(value, JsonValue::Number(_) | JsonValue::String(_))
+            }
+        };
+        // The placeholder is cast to the column type in PostgreSQL if `typed` is `true`.
+        let mut bind_value = |value: &JsonValue, typed: bool| {
+            arguments.push(value.clone());
+            let placeholder = Self::placeholder(arguments.len());
+            if cfg!(feature = "orm-postgres") && typed {
+                let column_type = migration::cast_column_type(col.column_type());
+                format!("{placeholder}::{column_type}")
+            } else {
+                placeholder.into_owned()
+            }
+        };
+
+        let field = Self::format_field(key);
+        if let Some(filter) = value.as_object() {
+            let mut conditions = Vec::with_capacity(filter.len());
+            for (name, value) in filter {
+                let operator = match name.as_str() {
+                    "$eq" => "=",
+                    "$ne" => "<>",
+                    "$lt" => "<",
+                    "$le" => "<=",
+                    "$gt" => ">",
+                    "$ge" => ">=",
+                    "$in" => "IN",
+                    "$nin" => "NOT IN",
+                    "$between" => "BETWEEN",
+                    "$like" => "LIKE",
+                    _ => "",
+                };
+                let condition = if operator == "IN" || operator == "NOT IN" {
+                    match value.as_array() {
+                        // An empty list matches no rows for `IN` and all rows for `NOT IN`.
+                        Some(values) if values.is_empty() => {
+                            let condition = if operator == "IN" { "FALSE" } else { "TRUE" };
+                            condition.to_owned()
+                        }
+                        Some(values) if !values.iter().any(is_special_value) => {
+                            let values = values
+                                .iter()
+                                .map(|value| bind_value(value, true))
+                                .collect::<Vec<_>>()
+                                .join(",");
+                            format!(r#"{field} {operator} ({values})"#)
+                        }
+                        Some(_) => {
+                            let filter = Map::from_entry(name, value.clone());
+                            col.format_filter(key, &filter.into())
+                        }
+                        // A malformed list matches no rows.
+                        None => "FALSE".to_owned(),
+                    }
+                } else if operator == "BETWEEN" {
+                    if let Some(values) = value.as_array()
+                        && let [min_value, max_value, ..] = values.as_slice()
+                    {
+                        if is_special_value(min_value) || is_special_value(max_value) {
+                            let filter = Map::from_entry(name, value.clone());
+                            col.format_filter(key, &filter.into())
+                        } else {
+                            let min_value = bind_value(min_value, true);
+                            let max_value = bind_value(max_value, true);
+                            format!(r#"{field} BETWEEN {min_value} AND {max_value}"#)
+                        }
+                    } else {
+                        // A malformed range matches no rows.
+                        "FALSE".to_owned()
+                    }
+                } else if operator == "LIKE" {
+                    if value.is_string() && col.enum_type().is_none() {
+                        // Patterns are matched on the text representation of the column.
+                        let value = bind_value(value, false);
+                        if cfg!(feature = "orm-postgres") && !is_string {
+                            format!(r#"{field}::TEXT LIKE {value}"#)
+                        } else {
+                            format!(r#"{field} LIKE {value}"#)
+                        }
+                    } else {
+                        let filter = Map::from_entry(name, value.clone());
+                        col.format_filter(key, &filter.into())
+                    }
+                } else if !operator.is_empty() && !is_special_value(value) {
+                    let value = bind_value(value, true);
+                    format!(r#"{field} {operator} {value}"#)
+                } else {
+                    let filter = Map::from_entry(name, value.clone());
+                    col.format_filter(key, &filter.into())
+                };
+                if !condition.is_empty() {
+                    conditions.push(condition);
+                }
+            }
+            conditions.join(" AND ")
+        } else if let Some(range) = value.as_array() && range.len() == 2 {
+            if range.iter().any(is_special_value) {
+                col.format_filter(key, value)
+            } else {
+                let min_value = bind_value(&range[0], true);
+                let max_value = bind_value(&range[1], true);
+                format!(r#"{field} >= {min_value} AND {field} < {max_value}"#)
+            }
+        } else if is_special_value(value) {
+            col.format_filter(key, value)
+        } else if let Some(value) = value.as_str() && value.contains(',') {
+            if is_datetime {
+                if let Some((min_value, max_value)) = value.split_once(',') {
+                    let min_value = bind_value(&min_value.into(), true);
+                    let max_value = bind_value(&max_value.into(), true);
+                    format!(r#"{field} >= {min_value} AND {field} < {max_value}"#)
+                } else {
+                    String::new()
+                }
+            } else {
+                let values = value
+                    .split(',')
+                    .map(|s| bind_value(&s.into(), true))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(r#"{field} IN ({values})"#)
+            }
+        } else {
+            let value = bind_value(value, true);
+            format!(r#"{field} = {value}"#)
+        }
+    }
+
     /// Formats a query filter.
     fn format_filter(key: &str, value: &JsonValue) -> String {
         if let Some(filter) = value.as_object() {
@@ -277,7 +578,98 @@ pub(super) trait QueryExt<DB> {
             return String::new();
         }
 
+        if self.query_cursor().is_some() {
+            return format!("LIMIT {limit}");
+        }
+
         let offset = self.query_offset();
         format!("LIMIT {limit} OFFSET {offset}")
     }
 }
+
+/// Gets the column of the model by the key, which can be qualified by the model name.
+fn get_column<M: Schema>(key: &str) -> Option<&Column<'static>> {
+    M::get_column(key).or_else(|| {
+        key.strip_prefix(M::model_name())
+            .and_then(|s| s.strip_prefix('.'))
+            .and_then(M::get_column)
+    })
+}
+
+/// Binds the JSON value to the SQL query as an argument.
+pub(super) fn bind_argument<'q>(
+    query: SqlxQuery<'q, DatabaseDriver, <DatabaseDriver as HasArguments<'q>>::Arguments>,
+    value: &'q JsonValue,
+) -> SqlxQuery<'q, DatabaseDriver, <DatabaseDriver as HasArguments<'q>>::Arguments> {
+    match value {
+        JsonValue::Null => query.bind(Option::<String>::None),
+        JsonValue::Bool(value) => query.bind(*value),
+        JsonValue::Number(value) => {
+            if let Some(value) = value.as_i64() {
+                query.bind(value)
+            } else {
+                query.bind(value.as_f64())
+            }
+        }
+        JsonValue::String(value) => query.bind(value.as_str()),
+        _ => query.bind(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryExt;
+    use crate::{
+        extension::JsonObjectExt,
+        model::{Column, EncodeColumn, Query},
+        JsonValue, Map,
+    };
+
+    #[test]
+    fn it_formats_empty_list_filters() {
+        let col = Column::new("id", "Uuid", true);
+        let mut arguments = Vec::new();
+
+        let filter = Map::from_entry("$in", Vec::<JsonValue>::new());
+        let condition = Query::format_bound_filter(&col, "id", &filter.into(), &mut arguments);
+        assert_eq!(condition, "FALSE");
+
+        let filter = Map::from_entry("$nin", Vec::<JsonValue>::new());
+        let condition = Query::format_bound_filter(&col, "id", &filter.into(), &mut arguments);
+        assert_eq!(condition, "TRUE");
+
+        let filter = Map::from_entry("$between", vec!["2023-01-01"]);
+        let condition = Query::format_bound_filter(&col, "id", &filter.into(), &mut arguments);
+        assert_eq!(condition, "FALSE");
+        assert!(arguments.is_empty());
+    }
+
+    #[test]
+    fn it_falls_back_for_special_values() {
+        let col = Column::new("name", "String", true);
+        let mut arguments = Vec::new();
+
+        let filter = JsonValue::from(Map::from_entry("$in", vec!["alice", "null"]));
+        let condition = Query::format_bound_filter(&col, "name", &filter, &mut arguments);
+        assert_eq!(condition, col.format_filter("name", &filter));
+        assert!(!condition.is_empty());
+        assert!(arguments.is_empty());
+
+        let filter = JsonValue::from(Map::from_entry("$in", vec!["alice", "bob"]));
+        let condition = Query::format_bound_filter(&col, "name", &filter, &mut arguments);
+        assert!(condition.contains(" IN ("));
+        assert_eq!(arguments.len(), 2);
+    }
+
+    #[test]
+    fn it_binds_like_patterns_as_text() {
+        let col = Column::new("id", "Uuid", true);
+        let mut arguments = Vec::new();
+
+        let filter = Map::from_entry("$like", "0191%");
+        let condition = Query::format_bound_filter(&col, "id", &filter.into(), &mut arguments);
+        assert!(condition.contains(" LIKE "));
+        assert!(!condition.contains("::UUID"));
+        assert_eq!(arguments, vec![JsonValue::from("0191%")]);
+    }
+}
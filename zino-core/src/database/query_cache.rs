@@ -0,0 +1,77 @@
+use super::{tenant, Schema};
+use crate::{
+    cache::GlobalCache,
+    datetime::DateTime,
+    error::Error,
+    extension::{JsonObjectExt, JsonValueExt},
+    JsonValue, Map,
+};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::LazyLock, time::Duration};
+
+/// Returns the cache scope of the model in the current tenant scope,
+/// which consists of the connection pool and the table name qualified by the schema.
+fn cache_scope<M: Schema>() -> String {
+    let pool_name = tenant::current_pool_name();
+    let pool_name = pool_name.as_deref().unwrap_or(M::READER_NAME);
+    let table_name = M::qualified_table_name();
+    format!("{pool_name}:{table_name}")
+}
+
+/// Formats the cache key for the query of the model in [`GlobalCache`].
+/// The key contains the generation number of the cache scope,
+/// so that the results can be invalidated at once by bumping the generation.
+/// The tenant of the current scope is a part of the key, and `*` stands for all the tenants.
+pub(super) fn format_key<M: Schema>(sql: &str, arguments: &[JsonValue]) -> Result<String, Error> {
+    let tenant_id = tenant::current_tenant_id::<M>()?;
+    let tenant_id = tenant_id.as_deref().unwrap_or("*");
+    let scope = cache_scope::<M>();
+    let generation = QUERY_CACHE_GENERATIONS
+        .read()
+        .get(&scope)
+        .copied()
+        .unwrap_or_default();
+    let arguments = JsonValue::from(arguments.to_vec());
+    Ok(format!("query_cache:{scope}:{generation}:{tenant_id}:{sql}:{arguments}"))
+}
+
+/// Returns the cached value of the key if it has not expired.
+pub(super) fn get(key: &str) -> Option<JsonValue> {
+    if let Some(JsonValue::Object(mut entry)) = GlobalCache::get(key) {
+        let expires_at = entry.get_i64("expires_at").unwrap_or_default();
+        if expires_at > DateTime::current_timestamp_millis() {
+            return entry.remove("data");
+        }
+        GlobalCache::pop(key);
+    }
+    None
+}
+
+/// Puts the value into the cache with the TTL.
+pub(super) fn put(key: String, value: impl Into<JsonValue>, ttl: Duration) {
+    let ttl_millis = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+    let mut entry = Map::new();
+    entry.upsert(
+        "expires_at",
+        DateTime::current_timestamp_millis().saturating_add(ttl_millis),
+    );
+    entry.upsert("data", value.into());
+    GlobalCache::put(key, entry);
+}
+
+/// Decodes the cached value as a list of models.
+#[inline]
+pub(super) fn decode_models(value: JsonValue) -> Vec<Map> {
+    value.into_map_array()
+}
+
+/// Invalidates all the cached results of the model in the current tenant scope.
+pub(super) fn invalidate<M: Schema>() {
+    let mut generations = QUERY_CACHE_GENERATIONS.write();
+    let generation = generations.entry(cache_scope::<M>()).or_default();
+    *generation = generation.wrapping_add(1);
+}
+
+/// Generation numbers of the cache scopes.
+static QUERY_CACHE_GENERATIONS: LazyLock<RwLock<HashMap<String, u64>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
@@ -0,0 +1,85 @@
+use super::scope;
+use crate::{trace::TimingMetric, Uuid};
+use std::{
+    cell::RefCell,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Statistics of the queries executed in a scope, which is usually a request-response cycle.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    /// Request ID.
+    request_id: Uuid,
+    /// Total execution time of the queries in microseconds.
+    execution_time_micros: Arc<AtomicU64>,
+    /// Number of the queries.
+    num_queries: Arc<AtomicU64>,
+}
+
+impl QueryStats {
+    /// Creates a new instance for the request.
+    #[inline]
+    pub fn new(request_id: Uuid) -> Self {
+        Self {
+            request_id,
+            execution_time_micros: Arc::new(AtomicU64::new(0)),
+            num_queries: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Records the execution time of a query.
+    #[inline]
+    pub fn record(&self, execution_time: Duration) {
+        let execution_time_micros = u64::try_from(execution_time.as_micros()).unwrap_or(u64::MAX);
+        self.execution_time_micros
+            .fetch_add(execution_time_micros, Relaxed);
+        self.num_queries.fetch_add(1, Relaxed);
+    }
+
+    /// Returns the request ID.
+    #[inline]
+    pub fn request_id(&self) -> Uuid {
+        self.request_id
+    }
+
+    /// Returns the total execution time of the queries.
+    #[inline]
+    pub fn execution_time(&self) -> Duration {
+        Duration::from_micros(self.execution_time_micros.load(Relaxed))
+    }
+
+    /// Returns the number of the queries.
+    #[inline]
+    pub fn num_queries(&self) -> u64 {
+        self.num_queries.load(Relaxed)
+    }
+
+    /// Returns the `db` metric for the `Server-Timing` header.
+    /// It is `None` if there are no queries executed.
+    pub fn timing_metric(&self) -> Option<TimingMetric> {
+        (self.num_queries() > 0)
+            .then(|| TimingMetric::new("db".into(), None, Some(self.execution_time())))
+    }
+
+    /// Returns the query statistics of the current scope.
+    #[inline]
+    pub fn current() -> Option<Self> {
+        CURRENT_QUERY_STATS.with(|stats| stats.borrow().clone())
+    }
+
+    /// Runs the future with the query statistics as the context of the current scope.
+    #[inline]
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        scope::run(&CURRENT_QUERY_STATS, self, future).await
+    }
+}
+
+thread_local! {
+    /// Query statistics of the current scope.
+    static CURRENT_QUERY_STATS: RefCell<Option<QueryStats>> = const { RefCell::new(None) };
+}
@@ -1,17 +1,36 @@
 use super::{
-    mutation::MutationExt, query::QueryExt, ConnectionPool, DatabaseDriver, DatabaseRow,
-    ModelHelper,
+    aggregation::AggregationExt,
+    join::{self, DecodeJoin},
+    migration,
+    mutation::MutationExt,
+    query::{self, QueryExt},
+    tenant, ConnectionPool, DatabaseDriver, DatabaseRow, ModelHelper, TransactionContext,
 };
 use crate::{
+    datetime::DateTime,
     error::Error,
     extension::JsonObjectExt,
-    model::{Column, DecodeRow, EncodeColumn, ModelHooks, Mutation, Query, QueryContext},
+    model::{
+        Aggregation, Column, DecodeRow, EncodeColumn, Index, ModelHooks, Mutation, Query,
+        QueryContext,
+    },
     BoxFuture, JsonValue, Map, Uuid,
 };
-use futures::TryStreamExt;
+use futures::{
+    channel::mpsc,
+    future::{self, FutureExt},
+    stream::{self, BoxStream, StreamExt},
+    SinkExt, TryStreamExt,
+};
 use serde::de::DeserializeOwned;
-use sqlx::{Decode, Row, Transaction, Type};
-use std::{fmt::Display, sync::atomic::Ordering::Relaxed};
+use sqlx::{Decode, Executor, Pool, Row, Transaction, Type};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::atomic::Ordering::Relaxed,
+    time::Duration,
+};
 
 /// Database schema.
 pub trait Schema: 'static + Send + Sync + ModelHooks {
@@ -28,6 +47,14 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     const WRITER_NAME: &'static str = "main";
     /// Optional custom table name.
     const TABLE_NAME: Option<&'static str> = None;
+    /// Optional tenant key name specified by `#[schema(tenant_id_field = "tenant_id")]`.
+    /// If it is specified, the rows are isolated by the tenant of the current scope
+    /// with the tenant predicate in the filters, and the tenant is stamped on the inserts.
+    /// See [`TenantContext`](super::TenantContext) for details.
+    const TENANT_KEY_NAME: Option<&'static str> = None;
+    /// Optional TTL of the query cache, specified by `#[schema(cache_ttl = 60)]` in seconds.
+    /// The query cache requires the `cache` feature and is disabled if it is `None`.
+    const QUERY_CACHE_TTL: Option<Duration> = None;
 
     /// Returns the primary key value.
     fn primary_key(&self) -> &Self::PrimaryKey;
@@ -47,12 +74,29 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Returns a reference to the writeonly column fields.
     fn writeonly_fields() -> &'static [&'static str];
 
+    /// Returns a reference to the composite indexes.
+    #[inline]
+    fn indexes() -> &'static [Index<'static>] {
+        &[]
+    }
+
     /// Retrieves a connection pool for the model reader.
     async fn acquire_reader() -> Result<&'static ConnectionPool, Error>;
 
     /// Retrieves a connection pool for the model writer.
     async fn acquire_writer() -> Result<&'static ConnectionPool, Error>;
 
+    /// Acquires the database reader for the query.
+    /// The writer is used if the `read_your_writes` flag of the query has been enabled.
+    #[inline]
+    async fn acquire_reader_for(query: &Query) -> Result<&'static ConnectionPool, Error> {
+        if query.read_your_writes() {
+            Self::acquire_writer().await
+        } else {
+            Self::acquire_reader().await
+        }
+    }
+
     /// Returns the model name.
     #[inline]
     fn model_name() -> &'static str {
@@ -77,6 +121,30 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         })
     }
 
+    /// Returns the table name qualified by the schema of the tenant in the current scope.
+    /// It is the same as the table name if the tenant is not bound to a schema.
+    #[inline]
+    fn qualified_table_name() -> Cow<'static, str> {
+        tenant::qualify_table_name(Self::table_name())
+    }
+
+    /// Invalidates the query cache of the model in the current tenant scope.
+    /// It is called automatically by the write operations of the model.
+    #[inline]
+    fn invalidate_query_cache() {
+        #[cfg(feature = "cache")]
+        if Self::QUERY_CACHE_TTL.is_some() {
+            super::query_cache::invalidate::<Self>();
+        }
+    }
+
+    /// Registers the model schema with the validation rules of the columns
+    /// into the OpenAPI components.
+    #[inline]
+    fn register_openapi_schema() {
+        crate::openapi::register_model_schema(Self::model_name(), Self::columns());
+    }
+
     /// Constructs a default `Query` for the model.
     #[inline]
     fn default_query() -> Query {
@@ -126,16 +194,20 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Initializes the model reader.
     #[inline]
     fn init_reader() -> Result<&'static ConnectionPool, Error> {
+        let pool_name = tenant::current_pool_name();
+        let name = pool_name.as_deref().unwrap_or(Self::READER_NAME);
         super::SHARED_CONNECTION_POOLS
-            .get_pool(Self::READER_NAME)
+            .get_reader(name)
             .ok_or_else(|| Error::new("connection to the database is unavailable"))
     }
 
     /// Initializes the model writer.
     #[inline]
     fn init_writer() -> Result<&'static ConnectionPool, Error> {
+        let pool_name = tenant::current_pool_name();
+        let name = pool_name.as_deref().unwrap_or(Self::WRITER_NAME);
         super::SHARED_CONNECTION_POOLS
-            .get_pool(Self::WRITER_NAME)
+            .get_pool(name)
             .ok_or_else(|| Error::new("connection to the database is unavailable"))
     }
 
@@ -145,35 +217,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Self::before_create_table().await?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let table_name = Self::table_name();
-        let columns = Self::columns()
+        let table_name = Self::qualified_table_name();
+        let columns = Self::columns();
+        for sql in columns.iter().filter_map(format_enum_type_statement) {
+            sqlx::query(&sql).execute(pool).await?;
+        }
+
+        let foreign_keys = columns.iter().filter_map(format_foreign_key);
+        let columns = columns
             .iter()
-            .map(|col| {
-                let column_name = col.name();
-                let column_type = col.column_type();
-                let mut column = format!("{column_name} {column_type}");
-                if column_name == primary_key_name {
-                    column += " PRIMARY KEY";
-                } else if let Some(value) = col.default_value() {
-                    if col.auto_increment() {
-                        column += if cfg!(feature = "orm-mysql") {
-                            " AUTO_INCREMENT"
-                        } else {
-                            " AUTOINCREMENT"
-                        };
-                    } else {
-                        let value = col.format_value(value);
-                        if cfg!(feature = "orm-sqlite") && value.contains('(') {
-                            column = format!("{column} DEFAULT ({value})");
-                        } else {
-                            column = format!("{column} DEFAULT {value}");
-                        }
-                    }
-                } else if col.is_not_null() {
-                    column += " NOT NULL";
-                }
-                column
-            })
+            .map(|col| format_column_definition(col, primary_key_name))
+            .chain(foreign_keys)
             .collect::<Vec<_>>()
             .join(",\n  ");
         let sql = format!("CREATE TABLE IF NOT EXISTS {table_name} (\n  {columns}\n);");
@@ -186,160 +240,182 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     async fn create_indexes() -> Result<u64, Error> {
         let pool = Self::init_writer()?.pool();
 
-        let table_name = Self::table_name();
-        let columns = Self::columns();
-        let mut rows = 0;
+        let table_name = Self::qualified_table_name();
         if cfg!(feature = "orm-mysql") {
             let sql = format!("SHOW INDEXES FROM {table_name}");
             let indexes = sqlx::query(&sql).fetch_all(pool).await?;
             if indexes.len() > 1 {
                 return Ok(0);
             }
+        }
 
-            let mut text_search_columns = Vec::new();
-            for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
-                    if matches!(index_type, "fulltext" | "text") {
-                        text_search_columns.push(column_name);
-                    } else if matches!(index_type, "unique" | "spatial") {
-                        let index_type = index_type.to_uppercase();
-                        let sql = format!(
-                            "CREATE {index_type} INDEX {table_name}_{column_name}_index \
-                                ON {table_name} ({column_name});"
-                        );
-                        rows = sqlx::query(&sql)
-                            .execute(pool)
-                            .await?
-                            .rows_affected()
-                            .max(rows);
-                    } else if matches!(index_type, "btree" | "hash") {
-                        let index_type = index_type.to_uppercase();
-                        let sql = format!(
-                            "CREATE INDEX {table_name}_{column_name}_index \
-                                ON {table_name} ({column_name}) USING {index_type};"
+        let mut rows = 0;
+        for (_, sql) in format_index_statements(&table_name, Self::columns(), Self::indexes()) {
+            rows = sqlx::query(&sql)
+                .execute(pool)
+                .await?
+                .rows_affected()
+                .max(rows);
+        }
+        Ok(rows)
+    }
+
+    /// Diffs the model columns and indexes against the live table,
+    /// and returns the statements to migrate the table.
+    /// If `dry_run` is `true`, the statements are logged but not executed;
+    /// otherwise they are executed and recorded as a new migration version
+    /// in a transaction, or one version per statement for MySQL.
+    ///
+    /// Columns which only exist in the live table are never dropped,
+    /// and an error is returned for a new `not_null` column without a default value.
+    /// Column types can not be altered in place for SQLite, so they are skipped with a warning.
+    /// Foreign key constraints are not added to the existing table.
+    async fn migrate(dry_run: bool) -> Result<Vec<String>, Error> {
+        let pool = Self::init_writer()?.pool();
+
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let qualified_table_name = Self::qualified_table_name();
+        let table_name = qualified_table_name.as_ref();
+        let live_columns = migration::fetch_table_columns(pool, table_name).await?;
+        if live_columns.is_empty() {
+            let message = format!("table `{table_name}` does not exist");
+            return Err(Error::new(message));
+        }
+
+        let columns = Self::columns();
+        let mut statements = Vec::new();
+        for col in columns {
+            let column_name = col.name();
+            let column_type = col.column_type();
+            if let Some((_, live_column_type)) =
+                live_columns.iter().find(|(name, _)| name == column_name)
+            {
+                if !migration::is_same_column_type(column_type, live_column_type) {
+                    if let Some(sql) =
+                        migration::format_alter_column_type(table_name, column_name, column_type)
+                    {
+                        statements.extend(format_enum_type_statement(col));
+                        if cfg!(feature = "orm-postgres") && col.enum_type().is_some() {
+                            // The default value can not be cast to the enum type automatically.
+                            let sql_drop_default = migration::format_alter_column_default(
+                                table_name,
+                                column_name,
+                                None,
+                            );
+                            statements.push(sql_drop_default);
+                            statements.push(sql);
+                            if let Some(value) = col.default_value() {
+                                let value = col.format_value(value);
+                                let sql_set_default = migration::format_alter_column_default(
+                                    table_name,
+                                    column_name,
+                                    Some(&value),
+                                );
+                                statements.push(sql_set_default);
+                            }
+                        } else {
+                            statements.push(sql);
+                        }
+                    } else {
+                        tracing::warn!(
+                            table_name,
+                            column_name,
+                            live_column_type,
+                            "column type `{column_type}` can not be altered in place"
                         );
-                        rows = sqlx::query(&sql)
-                            .execute(pool)
-                            .await?
-                            .rows_affected()
-                            .max(rows);
                     }
                 }
-            }
-            if !text_search_columns.is_empty() {
-                let text_search_columns = text_search_columns.join(", ");
-                let sql = format!(
-                    "CREATE FULLTEXT INDEX {table_name}_text_search_index \
-                        ON {table_name} ({text_search_columns});"
+            } else if col.is_not_null() && col.default_value().is_none() {
+                let message = format!(
+                    "column `{column_name}` can not be added to the table `{table_name}` \
+                        since it is not null without a default value"
                 );
-                rows = sqlx::query(&sql)
-                    .execute(pool)
-                    .await?
-                    .rows_affected()
-                    .max(rows);
+                return Err(Error::new(message));
+            } else {
+                let column = format_column_definition(col, primary_key_name);
+                statements.extend(format_enum_type_statement(col));
+                statements.push(format!("ALTER TABLE {table_name} ADD COLUMN {column};"));
             }
-        } else if cfg!(feature = "orm-postgres") {
-            let mut text_search_columns = Vec::new();
-            let mut text_search_languages = Vec::new();
-            for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
-                    if index_type.starts_with("text") {
-                        let language = index_type.strip_prefix("text:").unwrap_or("english");
-                        let column = format!("coalesce({column_name}, '')");
-                        text_search_languages.push(language);
-                        text_search_columns.push((language, column));
-                    } else if index_type == "unique" {
-                        let sql = format!(
-                            "CREATE UNIQUE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
-                                ON {table_name} ({column_name});"
-                        );
-                        rows = sqlx::query(&sql)
-                            .execute(pool)
-                            .await?
-                            .rows_affected()
-                            .max(rows);
-                    } else {
-                        let sort_order = if index_type == "btree" { " DESC" } else { "" };
-                        let sql = format!(
-                            "CREATE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
-                                ON {table_name} USING {index_type}({column_name}{sort_order});"
-                        );
-                        rows = sqlx::query(&sql)
-                            .execute(pool)
-                            .await?
-                            .rows_affected()
-                            .max(rows);
-                    }
-                }
+        }
+
+        let live_indexes = migration::fetch_table_indexes(pool, table_name).await?;
+        let indexes = Self::indexes();
+        for (index_name, sql) in format_index_statements(table_name, columns, indexes) {
+            if !live_indexes.contains(&index_name) {
+                statements.push(sql);
             }
-            for language in text_search_languages {
-                let text = text_search_columns
-                    .iter()
-                    .filter_map(|col| (col.0 == language).then_some(col.1.as_str()))
-                    .intersperse(" || ' ' || ")
-                    .collect::<String>();
-                let text_search = format!("to_tsvector('{language}', {text})");
-                let sql = format!(
-                    "CREATE INDEX IF NOT EXISTS {table_name}_text_search_{language}_index \
-                        ON {table_name} USING gin({text_search});"
-                );
-                rows = sqlx::query(&sql)
-                    .execute(pool)
-                    .await?
-                    .rows_affected()
-                    .max(rows);
+        }
+
+        if dry_run {
+            for sql in statements.iter() {
+                tracing::info!(table_name, dry_run, "{sql}");
             }
-        } else {
-            for col in columns {
-                if let Some(index_type) = col.index_type() {
-                    let column_name = col.name();
-                    let index_type = if index_type == "unique" { "UNIQUE" } else { "" };
-                    let sql = format!(
-                        "CREATE {index_type} INDEX IF NOT EXISTS {table_name}_{column_name}_index \
-                            ON {table_name} ({column_name});"
-                    );
-                    rows = sqlx::query(&sql)
-                        .execute(pool)
-                        .await?
-                        .rows_affected()
-                        .max(rows);
+        } else if !statements.is_empty() {
+            migration::create_migrations_table(pool).await?;
+            let version = if cfg!(feature = "orm-mysql") {
+                // DDL statements are committed implicitly in MySQL,
+                // so each of them is recorded once it has been applied.
+                let mut version = 0;
+                for sql in statements.iter() {
+                    sqlx::query(sql).execute(pool).await?;
+                    let applied = [sql.to_owned()];
+                    version = migration::record_migration(pool, table_name, &applied).await?;
                 }
-            }
+                version
+            } else {
+                let mut tx = pool.begin().await?;
+                for sql in statements.iter() {
+                    sqlx::query(sql).execute(&mut *tx).await?;
+                }
+
+                let version =
+                    migration::record_migration(&mut *tx, table_name, &statements).await?;
+                tx.commit().await?;
+                version
+            };
+            tracing::warn!(
+                table_name,
+                version,
+                "table `{table_name}` has been migrated"
+            );
         }
-        Ok(rows)
+        Ok(statements)
     }
 
     /// Inserts the model into the table.
-    async fn insert(mut self) -> Result<QueryContext, Error> {
+    async fn insert(self) -> Result<QueryContext, Error> {
         let pool = Self::acquire_writer().await?.pool();
-        let model_data = self.before_insert().await?;
+        let result = execute_insert(pool, self).await;
+        Self::invalidate_query_cache();
+        result
+    }
 
-        let map = self.into_map();
-        let table_name = Self::table_name();
-        let fields = Self::fields().join(", ");
-        let values = Self::columns()
-            .iter()
-            .map(|col| col.encode_value(map.get(col.name())))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let sql = format!("INSERT INTO {table_name} ({fields}) VALUES ({values});");
+    /// Inserts the model into the table, and decodes the stored row as `Self`.
+    /// The row is fetched by the `RETURNING` clause in PostgreSQL and SQLite (3.35+),
+    /// or by a follow-up select on the same connection in MySQL.
+    async fn insert_returning(mut self) -> Result<Self, Error>
+    where
+        Self: DecodeRow<DatabaseRow, Error = Error>,
+    {
+        let pool = Self::acquire_writer().await?.pool();
+        let model_data = self.before_insert().await?;
 
-        let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
-        let rows_affected = query_result.rows_affected();
-        let success = rows_affected == 1;
-        ctx.set_query(sql);
-        ctx.set_query_result(Some(rows_affected), success);
-        Self::after_scan(&ctx).await?;
-        Self::after_insert(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
+        let primary_key = if self.primary_key() == &<Self::PrimaryKey>::default() {
+            // The primary key is generated by the auto-increment column.
+            "LAST_INSERT_ID()".to_owned()
         } else {
-            Err(Error::new(format!(
-                "{rows_affected} rows are affected while it is expected to affect 1 row"
-            )))
+            Query::escape_string(self.primary_key())
+        };
+        let sql = format_insert_statement::<Self>(self.into_map())?;
+        let (ctx, row) = execute_returning::<Self>(pool, &sql, &[], &primary_key).await?;
+        Self::invalidate_query_cache();
+        Self::after_insert(&ctx, model_data).await?;
+        match row {
+            Some(row) => Self::decode_row(&row),
+            None => Err(Error::new(format!(
+                "{} rows are affected while it is expected to affect 1 row",
+                ctx.rows_affected().unwrap_or_default()
+            ))),
         }
     }
 
@@ -348,10 +424,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let columns = Self::columns();
         let mut values = Vec::with_capacity(models.len());
+        let mut data = Vec::with_capacity(models.len());
         for mut model in models.into_iter() {
-            let _model_data = model.before_insert().await?;
+            data.push(model.before_insert().await?);
 
-            let map = model.into_map();
+            let mut map = model.into_map();
+            tenant::stamp_tenant::<Self>(&mut map)?;
             let entries = columns
                 .iter()
                 .map(|col| col.encode_value(map.get(col.name())))
@@ -359,60 +437,57 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             values.push(format!("({})", entries.join(", ")));
         }
 
-        let table_name = Self::table_name();
+        let table_name = Self::qualified_table_name();
         let fields = Self::fields().join(", ");
         let values = values.join(", ");
         let sql = format!("INSERT INTO {table_name} ({fields}) VALUES {values};");
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let query_result = sqlx::query(&sql).execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
         ctx.set_query(sql);
         ctx.set_query_result(Some(rows_affected), true);
         Self::after_scan(&ctx).await?;
+        for model_data in data {
+            Self::after_insert(&ctx, model_data).await?;
+        }
         Ok(rows_affected)
     }
 
     /// Updates the model in the table.
-    async fn update(mut self) -> Result<QueryContext, Error> {
+    async fn update(self) -> Result<QueryContext, Error> {
+        let pool = Self::acquire_writer().await?.pool();
+        let result = execute_update(pool, self).await;
+        Self::invalidate_query_cache();
+        result
+    }
+
+    /// Updates the model in the table, and decodes the stored row as `Self`.
+    /// The row is fetched by the `RETURNING` clause in PostgreSQL and SQLite (3.35+),
+    /// or by a follow-up select on the same connection in MySQL.
+    async fn update_returning(mut self) -> Result<Self, Error>
+    where
+        Self: DecodeRow<DatabaseRow, Error = Error>,
+    {
         let pool = Self::acquire_writer().await?.pool();
         let model_data = self.before_update().await?;
 
-        let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let table_name = Self::table_name();
         let primary_key = Query::escape_string(self.primary_key());
         let map = self.into_map();
-        let readonly_fields = Self::readonly_fields();
-        let num_writable_fields = Self::fields().len() - readonly_fields.len();
-        let mut mutations = Vec::with_capacity(num_writable_fields);
-        for col in Self::columns() {
-            let field = col.name();
-            if !readonly_fields.contains(&field) {
-                let value = col.encode_value(map.get(field));
-                let field = Query::format_field(field);
-                mutations.push(format!("{field} = {value}"));
-            }
-        }
-
-        let mutations = mutations.join(", ");
-        let sql = format!(
-            "UPDATE {table_name} SET {mutations} WHERE {primary_key_name} = {primary_key};"
-        );
-
-        let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
-        let rows_affected = query_result.rows_affected();
-        let success = rows_affected == 1;
-        ctx.set_query(sql);
-        ctx.set_query_result(Some(rows_affected), success);
-        Self::after_scan(&ctx).await?;
+        let (sql, current_version) = format_update_statement::<Self>(&primary_key, map)?;
+        let arguments = Vec::from_iter(current_version.map(JsonValue::from));
+        let (ctx, row) = execute_returning::<Self>(pool, &sql, &arguments, &primary_key).await?;
+        Self::invalidate_query_cache();
         Self::after_update(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            Err(Error::new(format!(
-                "{rows_affected} rows are affected while it is expected to affect 1 row"
-            )))
+        match row {
+            Some(row) => Self::decode_row(&row),
+            None if current_version.is_some() => Err(version_conflict_error::<Self>()),
+            None => Err(Error::new(format!(
+                "{} rows are affected while it is expected to affect 1 row",
+                ctx.rows_affected().unwrap_or_default()
+            ))),
         }
     }
 
@@ -420,11 +495,45 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     async fn update_one(query: &Query, mutation: &mut Mutation) -> Result<QueryContext, Error> {
         let pool = Self::acquire_writer().await?.pool();
         Self::before_mutation(query, mutation).await?;
+        tenant::check_mutation::<Self>(mutation)?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
-        let filters = query.format_filters::<Self>();
-        let updates = mutation.format_updates::<Self>();
+        let mut arguments = Vec::new();
+        let mut filters = query.format_bound_filters::<Self>(&mut arguments);
+        let mut updates = mutation.format_updates::<Self>();
+
+        // Optimistic locking: the row is only updated if the current version is unchanged,
+        // which is specified by the query filters or derived from the next version
+        // in the updates, and the version is incremented if it is not updated explicitly.
+        let mut version_locked = false;
+        if Self::has_column("version") {
+            let field = Query::format_field("version");
+            let fields = mutation.fields();
+            let next_version = mutation
+                .updates()
+                .get_u64("version")
+                .filter(|_| fields.is_empty() || fields.iter().any(|f| f == "version"));
+            version_locked = query.filters().contains_key("version");
+            if !version_locked && let Some(version) = next_version.and_then(|v| v.checked_sub(1)) {
+                arguments.push(version.into());
+                let placeholder = Query::placeholder(arguments.len());
+                let condition = format!("{field} = {placeholder}");
+                filters = if filters.is_empty() {
+                    format!("WHERE {condition}")
+                } else {
+                    format!("{filters} AND {condition}")
+                };
+                version_locked = true;
+            }
+            if next_version.is_none() {
+                if !updates.is_empty() {
+                    updates.push_str(", ");
+                }
+                updates.push_str(&format!("{field} = {field} + 1"));
+            }
+        }
+
         let sql = if cfg!(feature = "orm-mysql") {
             // MySQL doesn't yet support 'LIMIT & IN/ALL/ANY/SOME subquery'
             // and self-referencing in UPDATE/DELETE
@@ -442,15 +551,28 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         };
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let query_result = sqlx_query.execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
-        let success = rows_affected <= 1;
-        ctx.set_query(sql);
+        let success = if version_locked {
+            rows_affected == 1
+        } else {
+            rows_affected <= 1
+        };
+        ctx.set_query(&sql);
         ctx.set_query_result(Some(rows_affected), success);
         Self::after_scan(&ctx).await?;
         Self::after_mutation(&ctx).await?;
         if success {
             Ok(ctx)
+        } else if rows_affected == 0 {
+            Err(version_conflict_error::<Self>())
         } else {
             Err(Error::new(format!(
                 "{rows_affected} rows are affected while it is expected to affect at most 1 row"
@@ -461,21 +583,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Updates many models selected by the query in the table.
     async fn update_many(query: &Query, mutation: &mut Mutation) -> Result<u64, Error> {
         let pool = Self::acquire_writer().await?.pool();
-        Self::before_mutation(query, mutation).await?;
-
-        let table_name = query.format_table_name::<Self>();
-        let filters = query.format_filters::<Self>();
-        let updates = mutation.format_updates::<Self>();
-        let sql = format!("UPDATE {table_name} SET {updates} {filters};");
-
-        let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
-        let rows_affected = query_result.rows_affected();
-        ctx.set_query(sql);
-        ctx.set_query_result(Some(rows_affected), true);
-        Self::after_scan(&ctx).await?;
-        Self::after_mutation(&ctx).await?;
-        Ok(rows_affected)
+        let result = execute_update_many::<Self, _>(pool, query, mutation).await;
+        Self::invalidate_query_cache();
+        result
     }
 
     /// Updates or inserts the model into the table.
@@ -483,8 +593,10 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let model_data = self.before_upsert().await?;
 
-        let map = self.into_map();
-        let table_name = Self::table_name();
+        let mut map = self.into_map();
+        tenant::stamp_tenant::<Self>(&mut map)?;
+        let tenant_condition = tenant::format_upsert_condition::<Self>()?;
+        let table_name = Self::qualified_table_name();
         let fields = Self::fields();
         let num_fields = fields.len();
         let readonly_fields = Self::readonly_fields();
@@ -496,7 +608,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             let value = col.encode_value(map.get(field));
             if !readonly_fields.contains(&field) {
                 let field = Query::format_field(field);
-                mutations.push(format!("{field} = {value}"));
+                let condition = tenant_condition.as_deref();
+                mutations.push(format_upsert_assignment(&field, &value, condition));
             }
             values.push(value);
         }
@@ -511,16 +624,21 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             )
         } else {
             let primary_key_name = Self::PRIMARY_KEY_NAME;
+            let tenant_condition = tenant_condition
+                .map(|condition| format!(" WHERE {condition}"))
+                .unwrap_or_default();
 
             // Both PostgreQL and SQLite (3.24+) support this syntax.
             format!(
                 "INSERT INTO {table_name} ({fields}) VALUES ({values}) \
-                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations};"
+                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations}{tenant_condition};"
             )
         };
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let query_result = sqlx::query(&sql).execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.set_query(sql);
@@ -536,53 +654,169 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 
-    /// Deletes the model in the table.
-    async fn delete(mut self) -> Result<QueryContext, Error> {
-        let pool = Self::acquire_writer().await?.pool();
-        let model_data = self.before_delete().await?;
+    /// Updates or inserts many models into the table.
+    ///
+    /// The conflict target defaults to the primary key, and the columns to be updated
+    /// default to all the columns. The read-only columns, the conflict target and
+    /// the tenant key are never updated.
+    /// MySQL detects the conflicts on any unique key, so the conflict target is ignored.
+    /// Models with the same values of the conflict target are deduplicated in the batch,
+    /// and the last one is kept.
+    async fn upsert_many(
+        models: Vec<Self>,
+        conflict_target: &[&str],
+        update_fields: &[&str],
+    ) -> Result<u64, Error> {
+        if models.is_empty() {
+            return Ok(0);
+        }
 
+        let pool = Self::acquire_writer().await?.pool();
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let table_name = Self::table_name();
-        let primary_key = self.primary_key();
-        let sql = if cfg!(feature = "orm-postgres") {
-            let primary_key = Query::escape_string(primary_key);
-            format!("DELETE FROM {table_name} WHERE {primary_key_name} = {primary_key};")
+        let conflict_target = if conflict_target.is_empty() {
+            vec![primary_key_name]
         } else {
-            let placeholder = Query::placeholder(1);
-            format!("DELETE FROM {table_name} WHERE {primary_key_name} = {placeholder};")
+            conflict_target.to_vec()
         };
+        if let Some(field) = conflict_target
+            .iter()
+            .chain(update_fields)
+            .find(|field| !Self::has_column(field))
+        {
+            let model_name = Self::model_name();
+            return Err(Error::new(format!(
+                "the model `{model_name}` does not have the column `{field}`"
+            )));
+        }
 
-        let mut ctx = Self::before_scan(&sql).await?;
-        let query = if cfg!(feature = "orm-postgres") {
-            sqlx::query(&sql)
+        // A row can not be affected twice by the `ON CONFLICT DO UPDATE` command.
+        let columns = Self::columns();
+        let mut values = Vec::with_capacity(models.len());
+        let mut conflict_keys = HashMap::with_capacity(models.len());
+        let mut data = Vec::with_capacity(models.len());
+        for mut model in models.into_iter() {
+            let model_data = model.before_upsert().await?;
+            let mut map = model.into_map();
+            tenant::stamp_tenant::<Self>(&mut map)?;
+            let conflict_key = conflict_target
+                .iter()
+                .filter_map(|&field| Self::get_column(field))
+                .map(|col| col.encode_value(map.get(col.name())).into_owned())
+                .collect::<Vec<_>>();
+            let entries = columns
+                .iter()
+                .map(|col| col.encode_value(map.get(col.name())))
+                .collect::<Vec<_>>();
+            let value = format!("({})", entries.join(", "));
+            if conflict_key.iter().any(|key| key == "NULL") {
+                values.push(value);
+                data.push(model_data);
+            } else if let Some(&index) = conflict_keys.get(&conflict_key) {
+                values[index] = value;
+                data[index] = model_data;
+            } else {
+                conflict_keys.insert(conflict_key, values.len());
+                values.push(value);
+                data.push(model_data);
+            }
+        }
+
+        let readonly_fields = Self::readonly_fields();
+        let tenant_key_name = Self::TENANT_KEY_NAME;
+        let update_fields = if update_fields.is_empty() {
+            Self::fields()
         } else {
-            ctx.add_argument(primary_key.to_string());
-            sqlx::query(&sql).bind(primary_key.to_string())
+            update_fields
         };
-        let query_result = query.execute(pool).await?;
+        let update_fields = update_fields
+            .iter()
+            .filter(|&&field| {
+                !readonly_fields.contains(&field)
+                    && !conflict_target.contains(&field)
+                    && tenant_key_name != Some(field)
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        let tenant_condition = tenant::format_upsert_condition::<Self>()?;
+        let mutations = update_fields
+            .into_iter()
+            .map(|field| {
+                let field = Query::format_field(field);
+                let value = if cfg!(feature = "orm-mysql") {
+                    format!("VALUES({field})")
+                } else {
+                    format!("excluded.{field}")
+                };
+                format_upsert_assignment(&field, &value, tenant_condition.as_deref())
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let table_name = Self::qualified_table_name();
+        let fields = Self::fields().join(", ");
+        let values = values.join(", ");
+        let sql = if cfg!(feature = "orm-mysql") {
+            let mutations = if mutations.is_empty() {
+                format!("{primary_key_name} = {primary_key_name}")
+            } else {
+                mutations
+            };
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES {values} \
+                    ON DUPLICATE KEY UPDATE {mutations};"
+            )
+        } else {
+            // Both PostgreQL and SQLite (3.24+) support this syntax.
+            let conflict_target = conflict_target.join(", ");
+            if mutations.is_empty() {
+                format!(
+                    "INSERT INTO {table_name} ({fields}) VALUES {values} \
+                        ON CONFLICT ({conflict_target}) DO NOTHING;"
+                )
+            } else {
+                let tenant_condition = tenant_condition
+                    .map(|condition| format!(" WHERE {condition}"))
+                    .unwrap_or_default();
+                format!(
+                    "INSERT INTO {table_name} ({fields}) VALUES {values} \
+                        ON CONFLICT ({conflict_target}) \
+                        DO UPDATE SET {mutations}{tenant_condition};"
+                )
+            }
+        };
+
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
+        let query_result = sqlx::query(&sql).execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
-        let success = rows_affected == 1;
         ctx.set_query(sql);
-        ctx.set_query_result(Some(rows_affected), success);
+        ctx.set_query_result(Some(rows_affected), true);
         Self::after_scan(&ctx).await?;
-        self.after_delete(&ctx, model_data).await?;
-        if success {
-            Ok(ctx)
-        } else {
-            Err(Error::new(format!(
-                "{rows_affected} rows are affected while it is expected to affect 1 row"
-            )))
+        for model_data in data {
+            Self::after_upsert(&ctx, model_data).await?;
         }
+        Ok(rows_affected)
+    }
+
+    /// Deletes the model in the table.
+    async fn delete(self) -> Result<QueryContext, Error> {
+        let pool = Self::acquire_writer().await?.pool();
+        let result = execute_delete(pool, self).await;
+        Self::invalidate_query_cache();
+        result
     }
 
     /// Deletes at most one model selected by the query in the table.
     async fn delete_one(query: &Query) -> Result<QueryContext, Error> {
         let pool = Self::acquire_writer().await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
         let table_name = query.format_table_name::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_bound_filters::<Self>(&mut arguments);
         let sort = query.format_sort();
         let sql = format!(
             "DELETE FROM {table_name} WHERE {primary_key_name} IN \
@@ -590,10 +824,17 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         );
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let query_result = sqlx_query.execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
-        ctx.set_query(sql);
+        ctx.set_query(&sql);
         ctx.set_query_result(Some(rows_affected), success);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -609,20 +850,82 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     /// Deletes many models selected by the query in the table.
     async fn delete_many(query: &Query) -> Result<u64, Error> {
         let pool = Self::acquire_writer().await?.pool();
-        Self::before_query(query).await?;
+        let result = execute_delete_many::<Self, _>(pool, query).await;
+        Self::invalidate_query_cache();
+        result
+    }
 
-        let table_name = query.format_table_name::<Self>();
-        let filters = query.format_filters::<Self>();
-        let sql = format!("DELETE FROM {table_name} {filters};");
+    /// Logically deletes the model by setting the `status` as `Deleted`
+    /// and the `deleted_at` timestamp, if the model has these columns.
+    async fn soft_delete(mut self) -> Result<QueryContext, Error> {
+        let pool = Self::acquire_writer().await?.pool();
+        let model_data = self.before_soft_delete().await?;
+
+        let mutation = Mutation::new(soft_delete_updates::<Self>()?);
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let table_name = Self::qualified_table_name();
+        let primary_key = self.primary_key();
+        let updates = mutation.format_updates::<Self>();
+        let tenant_condition = tenant::format_tenant_condition::<Self>()?;
+        let placeholder = format_primary_key_placeholder::<Self>();
+        let sql = format!(
+            "UPDATE {table_name} SET {updates} \
+                WHERE {primary_key_name} = {placeholder}{tenant_condition};"
+        );
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let query_result = sqlx::query(&sql).execute(pool).await?;
+        ctx.set_model_name(Self::model_name());
+        ctx.add_argument(primary_key.to_string());
+        let query = sqlx::query(&sql).bind(primary_key.to_string());
+        let query_result = query.execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
+        let success = rows_affected == 1;
         ctx.set_query(sql);
-        ctx.set_query_result(Some(rows_affected), true);
+        ctx.set_query_result(Some(rows_affected), success);
         Self::after_scan(&ctx).await?;
-        Self::after_query(&ctx).await?;
-        Ok(rows_affected)
+        Self::after_soft_delete(&ctx, model_data).await?;
+        if success {
+            Ok(ctx)
+        } else {
+            Err(Error::new(format!(
+                "{rows_affected} rows are affected while it is expected to affect 1 row"
+            )))
+        }
+    }
+
+    /// Logically deletes many models selected by the query in the table.
+    async fn soft_delete_many(query: &Query) -> Result<u64, Error> {
+        let mut mutation = Mutation::new(soft_delete_updates::<Self>()?);
+        Self::update_many(query, &mut mutation).await
+    }
+
+    /// Restores the logically deleted models selected by the query in the table.
+    /// The `status` is reset to the default value of the column,
+    /// and the `deleted_at` timestamp is cleared.
+    async fn restore(query: &Query) -> Result<u64, Error> {
+        let mut query = query.clone();
+        let mut updates = Map::new();
+        if has_deleted_status::<Self>() {
+            let default_status = Self::get_column("status").and_then(|col| col.default_value());
+            query.add_filter("status", "Deleted");
+            updates.upsert("status", default_status.unwrap_or("Active"));
+        }
+        if Self::has_column("deleted_at") {
+            if updates.is_empty() {
+                query.add_filter("deleted_at", "notnull");
+            }
+            updates.upsert("deleted_at", JsonValue::Null);
+        }
+        if updates.is_empty() {
+            let model_name = Self::model_name();
+            return Err(Error::new(format!(
+                "405 Method Not Allowed: the model `{model_name}` does not support soft deletes"
+            )));
+        }
+
+        let mut mutation = Mutation::new(updates);
+        Self::update_many(&query, &mut mutation).await
     }
 
     /// Finds a list of models selected by the query in the table,
@@ -630,29 +933,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     async fn find<T: DecodeRow<DatabaseRow, Error = Error>>(
         query: &Query,
     ) -> Result<Vec<T>, Error> {
-        let pool = Self::acquire_reader().await?.pool();
-        Self::before_query(query).await?;
-
-        let table_name = query.format_table_name::<Self>();
-        let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
-        let sort = query.format_sort();
-        let pagination = query.format_pagination();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
-
-        let mut ctx = Self::before_scan(&sql).await?;
-        let mut rows = sqlx::query(&sql).fetch(pool);
-        let mut data = Vec::new();
-        let mut max_rows = super::MAX_ROWS.load(Relaxed);
-        while let Some(row) = rows.try_next().await? && max_rows > 0 {
-            data.push(T::decode_row(&row)?);
-            max_rows -= 1;
-        }
-        ctx.set_query(&sql);
-        ctx.set_query_result(Some(u64::try_from(data.len())?), true);
-        Self::after_scan(&ctx).await?;
-        Self::after_query(&ctx).await?;
-        Ok(data)
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        execute_find::<Self, _, _>(pool, query).await
     }
 
     /// Finds a list of models selected by the query in the table,
@@ -667,31 +949,140 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(data.into()).map_err(Error::from)
     }
 
-    /// Finds one model selected by the query in the table,
-    /// and decodes it as an instance of type `T`.
-    async fn find_one<T: DecodeRow<DatabaseRow, Error = Error>>(
+    /// Finds a list of models whose `foreign_key` matches one of the `values`,
+    /// and decodes it as `Vec<T>`. The values are deduplicated and selected in chunks.
+    /// A warning is logged if the rows of a chunk are truncated by the maximum rows.
+    async fn find_related_as<T: DeserializeOwned>(
         query: &Query,
-    ) -> Result<Option<T>, Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        foreign_key: &str,
+        values: Vec<JsonValue>,
+    ) -> Result<Vec<T>, Error> {
+        /// Maximum number of values in the `$in` filter of a chunk.
+        const CHUNK_SIZE: usize = 1000;
+
+        let keys = dedup_keys(values);
+        let max_rows = super::MAX_ROWS.load(Relaxed);
+        let mut data = Vec::new();
+        for chunk in keys.chunks(CHUNK_SIZE) {
+            let mut query = query.clone();
+            query.add_filter(foreign_key, Map::from_entry("$in", chunk.to_vec()));
+            query.set_limit(usize::MAX);
+
+            let mut models = Self::find_as::<T>(&query).await?;
+            if models.len() >= max_rows {
+                let model_name = Self::model_name();
+                tracing::warn!(
+                    model_name,
+                    max_rows,
+                    "the related models of `{foreign_key}` have been truncated"
+                );
+            }
+            data.append(&mut models);
+        }
+        Ok(data)
+    }
+
+    /// Finds a list of models selected by the query in the table,
+    /// and caches the result if the query cache is enabled for the model.
+    ///
+    /// The query hooks are not called when the result is served from the cache.
+    async fn find_cached(query: &Query) -> Result<Vec<Map>, Error> {
+        #[cfg(feature = "cache")]
+        if let Some(ttl) = Self::QUERY_CACHE_TTL
+            && !query.read_your_writes()
+        {
+            tenant::check_access::<Self>()?;
+            Self::before_query(query).await?;
+
+            let (sql, arguments) = format_select_statement::<Self>(query);
+            let key = super::query_cache::format_key::<Self>(&sql, &arguments)?;
+            if let Some(data) = super::query_cache::get(&key) {
+                return Ok(super::query_cache::decode_models(data));
+            }
+
+            let pool = Self::acquire_reader_for(query).await?.pool();
+            let data = execute_select::<Self, Map, _>(pool, &sql, &arguments).await?;
+            super::query_cache::put(key, data.clone(), ttl);
+            return Ok(data);
+        }
+        Self::find(query).await
+    }
+
+    /// Finds a list of models selected by the query in the table,
+    /// and returns a stream which yields the decoded rows as they are fetched.
+    ///
+    /// The rows are fetched only when the stream is polled, so the `max-rows` limit
+    /// does not apply. The [`after_scan`](ModelHooks::after_scan) and
+    /// [`after_query`](ModelHooks::after_query) hooks are not called;
+    /// the query metrics are emitted when the stream is exhausted.
+    async fn find_stream<T>(query: &Query) -> Result<BoxStream<'static, Result<T, Error>>, Error>
+    where
+        T: DecodeRow<DatabaseRow, Error = Error> + Send + 'static,
+    {
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<Self>(Some(&mut arguments));
         let sort = query.format_sort();
-        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
+        let pagination = query.format_pagination();
+        let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let (num_rows, data) = if let Some(row) = sqlx::query(&sql).fetch_optional(pool).await? {
-            (1, Some(T::decode_row(&row)?))
-        } else {
-            (0, None)
+        ctx.set_model_name(Self::model_name());
+        for value in arguments.iter() {
+            ctx.add_argument(value);
+        }
+        ctx.set_query(&sql);
+
+        let (mut sender, receiver) = mpsc::channel(64);
+        let producer = async move {
+            let mut sqlx_query = sqlx::query(&sql);
+            for value in arguments.iter() {
+                sqlx_query = query::bind_argument(sqlx_query, value);
+            }
+
+            let mut rows = sqlx_query.fetch(pool);
+            let mut num_rows = 0;
+            let mut success = true;
+            loop {
+                let item = match rows.try_next().await {
+                    Ok(Some(row)) => T::decode_row(&row),
+                    Ok(None) => break,
+                    Err(err) => {
+                        success = false;
+                        Err(err.into())
+                    }
+                };
+                if sender.send(item).await.is_err() || !success {
+                    break;
+                }
+                num_rows += 1;
+            }
+            ctx.set_query_result(Some(num_rows), success);
+            if !success {
+                ctx.record_error("fail to select the models from the table");
+            }
+            ctx.emit_metrics("query");
         };
-        ctx.set_query(sql);
-        ctx.set_query_result(Some(num_rows), true);
-        Self::after_scan(&ctx).await?;
-        Self::after_query(&ctx).await?;
-        Ok(data)
+
+        // The producer is polled together with the receiver, so no task is spawned.
+        let producer = producer
+            .into_stream()
+            .filter_map(|_| future::ready(None::<Result<T, Error>>));
+        Ok(stream::select(receiver, producer).boxed())
+    }
+
+    /// Finds one model selected by the query in the table,
+    /// and decodes it as an instance of type `T`.
+    async fn find_one<T: DecodeRow<DatabaseRow, Error = Error>>(
+        query: &Query,
+    ) -> Result<Option<T>, Error> {
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        execute_find_one::<Self, _, _>(pool, query).await
     }
 
     /// Finds one model selected by the query in the table,
@@ -715,17 +1106,26 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     where
         T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
-        let table_name = Self::table_name();
+        let table_name = Self::qualified_table_name();
         let projection = query.format_projection();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<Self>(Some(&mut arguments));
         let sort = query.format_sort();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let scalar = sqlx::query_scalar(&sql).fetch_one(pool).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let row = sqlx_query.fetch_one(pool).await?;
+        let scalar = row.try_get(0)?;
         ctx.set_query(sql);
         ctx.set_query_result(Some(1), true);
         Self::after_scan(&ctx).await?;
@@ -739,18 +1139,26 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     where
         T: Send + Unpin + Type<DatabaseDriver> + for<'r> Decode<'r, DatabaseDriver>,
     {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<Self>(Some(&mut arguments));
         let sort = query.format_sort();
         let pagination = query.format_pagination();
         let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let mut rows = sqlx::query(&sql).fetch(pool);
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
         let mut data = Vec::new();
         let mut max_rows = super::MAX_ROWS.load(Relaxed);
         while let Some(row) = rows.try_next().await? && max_rows > 0 {
@@ -771,7 +1179,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         data: &mut Vec<Map>,
         columns: [&str; N],
     ) -> Result<u64, Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
@@ -794,11 +1203,18 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let table_name = query.format_table_name::<Self>();
         let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_bound_filters::<Self>(&mut arguments);
         let sql = format!("SELECT {projection} FROM {table_name} {filters};");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let mut rows = sqlx::query(&sql).fetch(pool);
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
         let mut associations = Vec::with_capacity(num_values);
         let translate_enabled = query.translate_enabled();
         while let Some(row) = rows.try_next().await? {
@@ -851,7 +1267,8 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         data: &mut Map,
         columns: [&str; N],
     ) -> Result<(), Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_query(query).await?;
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
@@ -870,13 +1287,20 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             return Ok(());
         }
 
-        let table_name = Self::table_name();
+        let table_name = Self::qualified_table_name();
         let projection = query.format_projection();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_bound_filters::<Self>(&mut arguments);
         let sql = format!("SELECT {projection} FROM {table_name} {filters};");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let mut rows = sqlx::query(&sql).fetch(pool);
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
         let mut associations = Vec::with_capacity(num_values);
         let translate_enabled = query.translate_enabled();
         while let Some(row) = rows.try_next().await? {
@@ -920,6 +1344,36 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Ok(())
     }
 
+    /// Eager loads the related models for `Vec<Map>` using a merged select
+    /// on the `foreign_key`, which solves the `N+1` problem. The values of the `local_key`
+    /// are matched with the `foreign_key`, and the related data is stored in the `relation`
+    /// field as an object, or as a list of objects if `many` is `true`.
+    /// The related models are selected by [`Schema::find_related_as()`].
+    async fn eager_load(
+        query: &mut Query,
+        data: &mut [Map],
+        local_key: &str,
+        foreign_key: &str,
+        relation: &str,
+        many: bool,
+    ) -> Result<u64, Error> {
+        let mut values = Vec::new();
+        for row in data.iter() {
+            match row.get(local_key) {
+                Some(JsonValue::Array(vec)) => values.extend(vec.iter().cloned()),
+                Some(JsonValue::Null) | None => (),
+                Some(value) => values.push(value.clone()),
+            }
+        }
+        if values.is_empty() {
+            return Ok(0);
+        }
+
+        let associations = Self::find_related_as::<Map>(query, foreign_key, values).await?;
+        associate_models(data, &associations, local_key, foreign_key, relation, many);
+        u64::try_from(associations.len()).map_err(Error::from)
+    }
+
     /// Performs a left outer join to another table to filter rows in the joined table,
     /// and decodes it as `Vec<T>`.
     async fn lookup<M: Schema, T: DecodeRow<DatabaseRow, Error = Error>>(
@@ -927,7 +1381,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         left_columns: &[&str],
         right_columns: &[&str],
     ) -> Result<Vec<T>, Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
+        tenant::check_access::<M>()?;
         Self::before_query(query).await?;
 
         let model_name = Self::model_name();
@@ -935,7 +1391,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let table_name = query.format_table_name::<Self>();
         let other_table_name = query.format_table_name::<M>();
         let projection = query.format_table_fields::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut filters = query.keyset_filters();
+        if let Some((field, tenant_id)) = tenant::qualified_tenant_filter::<Self>()? {
+            filters.to_mut().upsert(field, tenant_id);
+        }
+        let mut arguments = Vec::new();
+        let filters = Query::format_filters_with::<Self>(&filters, Some(&mut arguments));
         let sort = query.format_sort();
         let pagination = query.format_pagination();
         let on_expressions = left_columns
@@ -950,14 +1411,27 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             })
             .collect::<Vec<_>>()
             .join(" AND ");
+        let other_tenant_condition = tenant::qualified_tenant_filter::<M>()?
+            .map(|(field, tenant_id)| {
+                let field = Query::format_field(&field);
+                let tenant_id = Query::escape_string(tenant_id);
+                format!(" AND {field} = {tenant_id}")
+            })
+            .unwrap_or_default();
         let sql = format!(
             "SELECT {projection} FROM {table_name} \
                 LEFT OUTER JOIN {other_table_name} \
-                    ON {on_expressions} {filters} {sort} {pagination};"
+                    ON {on_expressions}{other_tenant_condition} {filters} {sort} {pagination};"
         );
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let mut rows = sqlx::query(&sql).fetch(pool);
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
         let mut data = Vec::new();
         let mut max_rows = super::MAX_ROWS.load(Relaxed);
         while let Some(row) = rows.try_next().await? && max_rows > 0 {
@@ -987,22 +1461,94 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(data.into()).map_err(Error::from)
     }
 
+    /// Finds a list of rows with the join clauses of the query,
+    /// and decodes them as tuples of models.
+    ///
+    /// The projection fields are aliased as `{model_name}__{field}` to avoid name conflicts,
+    /// and the filters on the columns of the model are qualified by the model name.
+    /// The sort order should use the qualified fields such as `{model_name}.{field}`.
+    async fn find_joined<T: DecodeJoin>(query: &Query) -> Result<Vec<T>, Error> {
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
+        Self::before_query(query).await?;
+
+        let table_name = query.format_table_name::<Self>();
+        let mut projection = vec![join::format_aliased_fields::<Self>(query)];
+        let mut joins = Vec::with_capacity(query.joins().len());
+        let mut arguments = Vec::new();
+        for clause in query.joins() {
+            projection.push(clause.projection().to_owned());
+            joins.push(clause.format_clause(&mut arguments));
+        }
+
+        let projection = projection.join(", ");
+        let joins = joins.join(" ");
+        let filters = join::qualify_filters::<Self>(&query.selection_filters::<Self>());
+        let filters = Query::format_filters_with::<Self>(&filters, Some(&mut arguments));
+        let sort = query.format_sort();
+        let pagination = query.format_pagination();
+        let sql = format!(
+            "SELECT {projection} FROM {table_name} {joins} {filters} {sort} {pagination};"
+        );
+
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
+        let mut data = Vec::new();
+        let mut max_rows = super::MAX_ROWS.load(Relaxed);
+        while let Some(row) = rows.try_next().await? && max_rows > 0 {
+            data.push(Map::decode_row(&row)?);
+            max_rows -= 1;
+        }
+        ctx.set_query(&sql);
+        ctx.set_query_result(Some(u64::try_from(data.len())?), true);
+        Self::after_scan(&ctx).await?;
+        Self::after_query(&ctx).await?;
+
+        let mut models = Vec::with_capacity(data.len());
+        for row in data {
+            models.push(T::decode_join(row).await?);
+        }
+        Ok(models)
+    }
+
     /// Counts the number of rows selected by the query in the table.
     async fn count(query: &Query) -> Result<u64, Error> {
         let pool = Self::acquire_writer().await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_count(query).await?;
 
-        let table_name = Self::table_name();
-        let filters = query.format_filters::<Self>();
-        let sql = format!("SELECT count(*) FROM {table_name} {filters};");
+        let (sql, arguments) = format_count_statement::<Self>(query);
+        execute_count::<Self, _>(pool, &sql, &arguments).await
+    }
 
-        let mut ctx = Self::before_scan(&sql).await?;
-        let count: i64 = sqlx::query_scalar(&sql).fetch_one(pool).await?;
-        ctx.set_query(sql);
-        ctx.set_query_result(Some(1), true);
-        Self::after_scan(&ctx).await?;
-        Self::after_count(&ctx).await?;
-        u64::try_from(count).map_err(Error::from)
+    /// Counts the number of rows selected by the query in the table,
+    /// and caches the result if the query cache is enabled for the model.
+    async fn count_cached(query: &Query) -> Result<u64, Error> {
+        #[cfg(feature = "cache")]
+        if let Some(ttl) = Self::QUERY_CACHE_TTL
+            && !query.read_your_writes()
+        {
+            tenant::check_access::<Self>()?;
+            Self::before_count(query).await?;
+
+            let (sql, arguments) = format_count_statement::<Self>(query);
+            let key = super::query_cache::format_key::<Self>(&sql, &arguments)?;
+            if let Some(count) = super::query_cache::get(&key).and_then(|v| v.as_u64()) {
+                return Ok(count);
+            }
+
+            let pool = Self::acquire_writer().await?.pool();
+            let count = execute_count::<Self, _>(pool, &sql, &arguments).await?;
+            super::query_cache::put(key, count, ttl);
+            return Ok(count);
+        }
+        Self::count(query).await
     }
 
     /// Counts the number of rows selected by the query in the table.
@@ -1012,10 +1558,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         columns: &[(&str, bool)],
     ) -> Result<T, Error> {
         let pool = Self::acquire_writer().await?.pool();
+        tenant::check_access::<Self>()?;
         Self::before_count(query).await?;
 
         let table_name = query.format_table_name::<Self>();
-        let filters = query.format_filters::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<Self>(Some(&mut arguments));
         let projection = columns
             .iter()
             .map(|&(key, distinct)| {
@@ -1035,7 +1583,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let sql = format!("SELECT {projection} FROM {table_name} {filters};");
 
         let mut ctx = Self::before_scan(&sql).await?;
-        let row = sqlx::query(&sql).fetch_one(pool).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let row = sqlx_query.fetch_one(pool).await?;
         ctx.set_query(sql);
         ctx.set_query_result(Some(1), true);
         Self::after_scan(&ctx).await?;
@@ -1053,9 +1607,58 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         serde_json::from_value(map.into()).map_err(Error::from)
     }
 
+    /// Aggregates the models selected by the query in the table,
+    /// and decodes the result as `Vec<T>`.
+    /// A warning is logged if the groups are truncated by the maximum rows.
+    async fn aggregate<T: DecodeRow<DatabaseRow, Error = Error>>(
+        query: &Query,
+        aggregation: &Aggregation,
+    ) -> Result<Vec<T>, Error> {
+        let pool = Self::acquire_reader_for(query).await?.pool();
+        tenant::check_access::<Self>()?;
+
+        let table_name = query.format_table_name::<Self>();
+        let (projection, groups) = aggregation.format_aggregation::<Self>();
+        let mut arguments = Vec::new();
+        let filters = query.format_selection_filters::<Self>(Some(&mut arguments));
+        let sql = format!("SELECT {projection} FROM {table_name} {filters} {groups};");
+
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments.iter() {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let mut rows = sqlx_query.fetch(pool);
+        let mut data = Vec::new();
+        let max_rows = super::MAX_ROWS.load(Relaxed);
+        while let Some(row) = rows.try_next().await? && data.len() < max_rows {
+            data.push(T::decode_row(&row)?);
+        }
+        if data.len() >= max_rows {
+            let model_name = Self::model_name();
+            tracing::warn!(model_name, max_rows, "the aggregation groups have been truncated");
+        }
+        ctx.set_query(&sql);
+        ctx.set_query_result(Some(u64::try_from(data.len())?), true);
+        Self::after_scan(&ctx).await?;
+        Ok(data)
+    }
+
+    /// Aggregates the models selected by the query in the table,
+    /// and parses the result as `Vec<T>`.
+    async fn aggregate_as<T: DeserializeOwned>(
+        query: &Query,
+        aggregation: &Aggregation,
+    ) -> Result<Vec<T>, Error> {
+        let data = Self::aggregate::<Map>(query, aggregation).await?;
+        serde_json::from_value(data.into()).map_err(Error::from)
+    }
+
     /// Executes the query in the table, and returns the total number of rows affected.
     async fn execute(query: &str, params: Option<&Map>) -> Result<u64, Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_writer().await?.pool();
         let (sql, values) = Query::prepare_query(query, params);
         let mut query = sqlx::query(&sql);
         let mut arguments = Vec::with_capacity(values.len());
@@ -1065,7 +1668,9 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let query_result = query.execute(pool).await?;
+        Self::invalidate_query_cache();
         let rows_affected = query_result.rows_affected();
         ctx.set_query(sql);
         ctx.append_arguments(&mut arguments);
@@ -1089,6 +1694,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let mut rows = query.fetch(pool);
         let mut data = Vec::new();
         let mut max_rows = super::MAX_ROWS.load(Relaxed);
@@ -1130,6 +1736,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let (num_rows, data) = if let Some(row) = query.fetch_optional(pool).await? {
             (1, Some(T::decode_row(&row)?))
         } else {
@@ -1171,6 +1778,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let scalar = query.fetch_one(pool).await?;
         ctx.set_query(sql);
         ctx.append_arguments(&mut arguments);
@@ -1194,6 +1802,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let mut rows = query.fetch(pool);
         let mut data = Vec::new();
         let mut max_rows = super::MAX_ROWS.load(Relaxed);
@@ -1208,6 +1817,13 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Ok(data)
     }
 
+    /// Begins a transaction on the connection pool of the model.
+    async fn begin_transaction() -> Result<TransactionContext<'static>, Error> {
+        let pool = Self::acquire_writer().await?.pool();
+        let transaction = pool.begin().await?;
+        Ok(TransactionContext::new(transaction))
+    }
+
     /// Executes the specific operations inside a transaction.
     /// If the operations return an error, the transaction will be rolled back;
     /// if not, the transaction will be committed.
@@ -1222,6 +1838,22 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         Ok(data)
     }
 
+    /// Executes the specific operations inside a transaction context.
+    /// If the operations return an error, the transaction will be rolled back;
+    /// if not, the transaction will be committed.
+    ///
+    /// The model operations on the transaction context run on the same connection,
+    /// and nested transactions can be created by savepoints.
+    async fn transaction_with<F, T>(tx: F) -> Result<T, Error>
+    where
+        F: for<'a> FnOnce(&'a mut TransactionContext<'static>) -> BoxFuture<'a, Result<T, Error>>,
+    {
+        let mut transaction = Self::begin_transaction().await?;
+        let data = tx(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(data)
+    }
+
     /// Finds one model selected by the primary key in the table,
     /// and decodes it as an instance of type `T`.
     async fn find_by_id<T: DecodeRow<DatabaseRow, Error = Error>>(
@@ -1230,22 +1862,26 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_reader().await?.pool();
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let table_name = Self::table_name();
+        let table_name = Self::qualified_table_name();
         let query = Self::default_query();
         let projection = query.format_projection();
+        let tenant_condition = tenant::format_tenant_condition::<Self>()?;
         let sql = if cfg!(feature = "orm-postgres") {
             let primary_key = Query::escape_string(primary_key);
             format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {primary_key};"
+                "SELECT {projection} FROM {table_name} \
+                    WHERE {primary_key_name} = {primary_key}{tenant_condition};"
             )
         } else {
             let placeholder = Query::placeholder(1);
             format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {placeholder};"
+                "SELECT {projection} FROM {table_name} \
+                    WHERE {primary_key_name} = {placeholder}{tenant_condition};"
             )
         };
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let query = if cfg!(feature = "orm-postgres") {
             sqlx::query(&sql)
         } else {
@@ -1265,26 +1901,31 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Finds one model selected by the primary key in the table, and parses it as `Self`.
+    /// The model is fetched by the writer since it is usually going to be updated.
     async fn try_get_model(primary_key: &Self::PrimaryKey) -> Result<Self, Error> {
-        let pool = Self::acquire_reader().await?.pool();
+        let pool = Self::acquire_writer().await?.pool();
 
         let primary_key_name = Self::PRIMARY_KEY_NAME;
-        let table_name = Self::table_name();
+        let table_name = Self::qualified_table_name();
         let query = Self::default_query();
         let projection = query.format_projection();
+        let tenant_condition = tenant::format_tenant_condition::<Self>()?;
         let sql = if cfg!(feature = "orm-postgres") {
             let primary_key = Query::escape_string(primary_key);
             format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {primary_key};"
+                "SELECT {projection} FROM {table_name} \
+                    WHERE {primary_key_name} = {primary_key}{tenant_condition};"
             )
         } else {
             let placeholder = Query::placeholder(1);
             format!(
-                "SELECT {projection} FROM {table_name} WHERE {primary_key_name} = {placeholder};"
+                "SELECT {projection} FROM {table_name} \
+                    WHERE {primary_key_name} = {placeholder}{tenant_condition};"
             )
         };
 
         let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_model_name(Self::model_name());
         let query = if cfg!(feature = "orm-postgres") {
             sqlx::query(&sql)
         } else {
@@ -1347,6 +1988,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             query.add_filter(field, value);
         }
         query.allow_fields(&fields);
+        query.add_filter("show_deleted", true);
         query.set_limit(2);
 
         let data = Self::find::<Map>(&query).await?;
@@ -1363,3 +2005,820 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 }
+
+/// Formats the `SELECT` statement of the query with the arguments to be bound.
+fn format_select_statement<M: Schema>(query: &Query) -> (String, Vec<JsonValue>) {
+    let table_name = query.format_table_name::<M>();
+    let projection = query.format_table_fields::<M>();
+    let mut arguments = Vec::new();
+    let filters = query.format_selection_filters::<M>(Some(&mut arguments));
+    let sort = query.format_sort();
+    let pagination = query.format_pagination();
+    let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} {pagination};");
+    (sql, arguments)
+}
+
+/// Formats the `SELECT count(*)` statement of the query with the arguments to be bound.
+fn format_count_statement<M: Schema>(query: &Query) -> (String, Vec<JsonValue>) {
+    let table_name = M::qualified_table_name();
+    let mut arguments = Vec::new();
+    let filters = query.format_selection_filters::<M>(Some(&mut arguments));
+    let sql = format!("SELECT count(*) FROM {table_name} {filters};");
+    (sql, arguments)
+}
+
+/// Formats the `DELETE` statement of the model restricted to the current tenant,
+/// with the arguments to be bound.
+pub(super) fn format_delete_statement<M: Schema>(
+    primary_key: &M::PrimaryKey,
+) -> Result<(String, Vec<JsonValue>), Error> {
+    let primary_key_name = M::PRIMARY_KEY_NAME;
+    let table_name = M::qualified_table_name();
+    let tenant_condition = tenant::format_tenant_condition::<M>()?;
+    let placeholder = format_primary_key_placeholder::<M>();
+    let sql = format!(
+        "DELETE FROM {table_name} \
+            WHERE {primary_key_name} = {placeholder}{tenant_condition};"
+    );
+    Ok((sql, vec![primary_key.to_string().into()]))
+}
+
+/// Formats the placeholder of the primary key as the first parameter,
+/// which is cast to the column type in PostgreSQL.
+fn format_primary_key_placeholder<M: Schema>() -> String {
+    let placeholder = Query::placeholder(1);
+    if cfg!(feature = "orm-postgres")
+        && let Some(col) = M::get_column(M::PRIMARY_KEY_NAME)
+    {
+        let column_type = migration::cast_column_type(col.column_type());
+        format!("{placeholder}::{column_type}")
+    } else {
+        placeholder.into_owned()
+    }
+}
+
+/// Formats the `INSERT` statement for the model data stamped with the tenant.
+pub(super) fn format_insert_statement<M: Schema>(mut map: Map) -> Result<String, Error> {
+    tenant::stamp_tenant::<M>(&mut map)?;
+    let table_name = M::qualified_table_name();
+    let fields = M::fields().join(", ");
+    let values = M::columns()
+        .iter()
+        .map(|col| col.encode_value(map.get(col.name())))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(format!("INSERT INTO {table_name} ({fields}) VALUES ({values});"))
+}
+
+/// Formats the `UPDATE` statement for the model data with the escaped primary key.
+/// It also returns the current version if the update is guarded by optimistic locking,
+/// which should be bound as the only argument of the statement.
+pub(super) fn format_update_statement<M: Schema>(
+    primary_key: &str,
+    mut map: Map,
+) -> Result<(String, Option<u64>), Error> {
+    tenant::stamp_tenant::<M>(&mut map)?;
+    let primary_key_name = M::PRIMARY_KEY_NAME;
+    let table_name = M::qualified_table_name();
+    let readonly_fields = M::readonly_fields();
+    let num_writable_fields = M::fields().len() - readonly_fields.len();
+    let mut mutations = Vec::with_capacity(num_writable_fields);
+    let mut current_version = None;
+    for col in M::columns() {
+        let field = col.name();
+        if !readonly_fields.contains(&field) {
+            if field == "version" {
+                // Optimistic locking: the row is only updated if the version is unchanged.
+                let field = Query::format_field(field);
+                mutations.push(format!("{field} = {field} + 1"));
+                current_version = Some(map.get_u64("version").unwrap_or_default());
+                continue;
+            }
+
+            let value = col.encode_value(map.get(field));
+            let field = Query::format_field(field);
+            mutations.push(format!("{field} = {value}"));
+        }
+    }
+
+    let mutations = mutations.join(", ");
+    let tenant_condition = tenant::format_tenant_condition::<M>()?;
+    let sql = if current_version.is_some() {
+        let version_field = Query::format_field("version");
+        let placeholder = Query::placeholder(1);
+        format!(
+            "UPDATE {table_name} SET {mutations} \
+                WHERE {primary_key_name} = {primary_key}{tenant_condition} \
+                AND {version_field} = {placeholder};"
+        )
+    } else {
+        format!(
+            "UPDATE {table_name} SET {mutations} \
+                WHERE {primary_key_name} = {primary_key}{tenant_condition};"
+        )
+    };
+    Ok((sql, current_version))
+}
+
+/// Inserts the model into the table with the executor.
+/// The query cache should be invalidated by the caller.
+pub(super) async fn execute_insert<'c, M, E>(
+    executor: E,
+    mut model: M,
+) -> Result<QueryContext, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let model_data = model.before_insert().await?;
+    let sql = format_insert_statement::<M>(model.into_map())?;
+
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let query_result = sqlx::query(&sql).execute(executor).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected == 1;
+    ctx.set_query(sql);
+    ctx.set_query_result(Some(rows_affected), success);
+    M::after_scan(&ctx).await?;
+    M::after_insert(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        Err(Error::new(format!(
+            "{rows_affected} rows are affected while it is expected to affect 1 row"
+        )))
+    }
+}
+
+/// Updates the model in the table with the executor.
+/// The query cache should be invalidated by the caller.
+pub(super) async fn execute_update<'c, M, E>(
+    executor: E,
+    mut model: M,
+) -> Result<QueryContext, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let model_data = model.before_update().await?;
+    let primary_key = Query::escape_string(model.primary_key());
+    let map = model.into_map();
+    let (sql, current_version) = format_update_statement::<M>(&primary_key, map)?;
+    let arguments = Vec::from_iter(current_version.map(JsonValue::from));
+
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(&sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let query_result = sqlx_query.execute(executor).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected == 1;
+    ctx.set_query(sql);
+    ctx.set_query_result(Some(rows_affected), success);
+    M::after_scan(&ctx).await?;
+    M::after_update(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else if rows_affected == 0 && current_version.is_some() {
+        Err(version_conflict_error::<M>())
+    } else {
+        Err(Error::new(format!(
+            "{rows_affected} rows are affected while it is expected to affect 1 row"
+        )))
+    }
+}
+
+/// Updates many models selected by the query in the table with the executor.
+/// The query cache should be invalidated by the caller.
+pub(super) async fn execute_update_many<'c, M, E>(
+    executor: E,
+    query: &Query,
+    mutation: &mut Mutation,
+) -> Result<u64, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    M::before_mutation(query, mutation).await?;
+    tenant::check_mutation::<M>(mutation)?;
+
+    let table_name = query.format_table_name::<M>();
+    let mut arguments = Vec::new();
+    let filters = query.format_bound_filters::<M>(&mut arguments);
+    let updates = mutation.format_updates::<M>();
+    let sql = format!("UPDATE {table_name} SET {updates} {filters};");
+
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(&sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let query_result = sqlx_query.execute(executor).await?;
+    let rows_affected = query_result.rows_affected();
+    ctx.set_query(&sql);
+    ctx.set_query_result(Some(rows_affected), true);
+    M::after_scan(&ctx).await?;
+    M::after_mutation(&ctx).await?;
+    Ok(rows_affected)
+}
+
+/// Deletes the model in the table with the executor.
+/// The query cache should be invalidated by the caller.
+pub(super) async fn execute_delete<'c, M, E>(
+    executor: E,
+    mut model: M,
+) -> Result<QueryContext, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let model_data = model.before_delete().await?;
+
+    let (sql, arguments) = format_delete_statement::<M>(model.primary_key())?;
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(&sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let query_result = sqlx_query.execute(executor).await?;
+    let rows_affected = query_result.rows_affected();
+    let success = rows_affected == 1;
+    ctx.set_query(sql);
+    ctx.set_query_result(Some(rows_affected), success);
+    M::after_scan(&ctx).await?;
+    model.after_delete(&ctx, model_data).await?;
+    if success {
+        Ok(ctx)
+    } else {
+        Err(Error::new(format!(
+            "{rows_affected} rows are affected while it is expected to affect 1 row"
+        )))
+    }
+}
+
+/// Deletes many models selected by the query in the table with the executor.
+/// The query cache should be invalidated by the caller.
+pub(super) async fn execute_delete_many<'c, M, E>(executor: E, query: &Query) -> Result<u64, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    tenant::check_access::<M>()?;
+    M::before_query(query).await?;
+    let model_data = M::before_delete_many(query).await?;
+
+    let table_name = query.format_table_name::<M>();
+    let mut arguments = Vec::new();
+    let filters = query.format_bound_filters::<M>(&mut arguments);
+    let sql = format!("DELETE FROM {table_name} {filters};");
+
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(&sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let query_result = sqlx_query.execute(executor).await?;
+    let rows_affected = query_result.rows_affected();
+    ctx.set_query(&sql);
+    ctx.set_query_result(Some(rows_affected), true);
+    M::after_scan(&ctx).await?;
+    M::after_query(&ctx).await?;
+    M::after_delete_many(&ctx, model_data).await?;
+    Ok(rows_affected)
+}
+
+/// Finds a list of models selected by the query in the table with the executor,
+/// and decodes it as `Vec<T>`.
+pub(super) async fn execute_find<'c, M, T, E>(executor: E, query: &Query) -> Result<Vec<T>, Error>
+where
+    M: Schema,
+    T: DecodeRow<DatabaseRow, Error = Error>,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    tenant::check_access::<M>()?;
+    M::before_query(query).await?;
+
+    let (sql, arguments) = format_select_statement::<M>(query);
+    execute_select::<M, T, _>(executor, &sql, &arguments).await
+}
+
+/// Executes the select statement with the executor, and decodes the rows as `Vec<T>`.
+async fn execute_select<'c, M, T, E>(
+    executor: E,
+    sql: &str,
+    arguments: &[JsonValue],
+) -> Result<Vec<T>, Error>
+where
+    M: Schema,
+    T: DecodeRow<DatabaseRow, Error = Error>,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let mut ctx = M::before_scan(sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let mut rows = sqlx_query.fetch(executor);
+    let mut data = Vec::new();
+    let mut max_rows = super::MAX_ROWS.load(Relaxed);
+    while let Some(row) = rows.try_next().await? && max_rows > 0 {
+        data.push(T::decode_row(&row)?);
+        max_rows -= 1;
+    }
+    ctx.set_query(sql);
+    ctx.set_query_result(Some(u64::try_from(data.len())?), true);
+    M::after_scan(&ctx).await?;
+    M::after_query(&ctx).await?;
+    Ok(data)
+}
+
+/// Executes the count statement with the executor.
+async fn execute_count<'c, M, E>(
+    executor: E,
+    sql: &str,
+    arguments: &[JsonValue],
+) -> Result<u64, Error>
+where
+    M: Schema,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    let mut ctx = M::before_scan(sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let count: i64 = sqlx_query.fetch_one(executor).await?.try_get(0)?;
+    ctx.set_query(sql);
+    ctx.set_query_result(Some(1), true);
+    M::after_scan(&ctx).await?;
+    M::after_count(&ctx).await?;
+    u64::try_from(count).map_err(Error::from)
+}
+
+/// Finds one model selected by the query in the table with the executor,
+/// and decodes it as an instance of type `T`.
+pub(super) async fn execute_find_one<'c, M, T, E>(
+    executor: E,
+    query: &Query,
+) -> Result<Option<T>, Error>
+where
+    M: Schema,
+    T: DecodeRow<DatabaseRow, Error = Error>,
+    E: Executor<'c, Database = DatabaseDriver>,
+{
+    tenant::check_access::<M>()?;
+    M::before_query(query).await?;
+
+    let table_name = query.format_table_name::<M>();
+    let projection = query.format_table_fields::<M>();
+    let mut arguments = Vec::new();
+    let filters = query.format_selection_filters::<M>(Some(&mut arguments));
+    let sort = query.format_sort();
+    let sql = format!("SELECT {projection} FROM {table_name} {filters} {sort} LIMIT 1;");
+
+    let mut ctx = M::before_scan(&sql).await?;
+    ctx.set_model_name(M::model_name());
+    let mut sqlx_query = sqlx::query(&sql);
+    for value in arguments.iter() {
+        sqlx_query = query::bind_argument(sqlx_query, value);
+        ctx.add_argument(value);
+    }
+    let (num_rows, data) = if let Some(row) = sqlx_query.fetch_optional(executor).await? {
+        (1, Some(T::decode_row(&row)?))
+    } else {
+        (0, None)
+    };
+    ctx.set_query(&sql);
+    ctx.set_query_result(Some(num_rows), true);
+    M::after_scan(&ctx).await?;
+    M::after_query(&ctx).await?;
+    Ok(data)
+}
+
+/// Formats an assignment of the conflict update in an upsert statement.
+/// For MySQL, the value is guarded by the tenant condition,
+/// since the `WHERE` clause is not supported by `ON DUPLICATE KEY UPDATE`.
+fn format_upsert_assignment(field: &str, value: &str, tenant_condition: Option<&str>) -> String {
+    if cfg!(feature = "orm-mysql")
+        && let Some(condition) = tenant_condition
+    {
+        format!("{field} = IF({condition}, {value}, {field})")
+    } else {
+        format!("{field} = {value}")
+    }
+}
+
+/// Executes the `INSERT` or `UPDATE` statement for a model, and fetches the stored row.
+async fn execute_returning<M: Schema>(
+    pool: &Pool<DatabaseDriver>,
+    sql: &str,
+    arguments: &[JsonValue],
+    primary_key: &str,
+) -> Result<(QueryContext, Option<DatabaseRow>), Error> {
+    // The fields are selected by the projection of the default query,
+    // so that the write-only columns are never returned.
+    let query = M::default_query();
+    let projection = query.format_projection();
+    if cfg!(feature = "orm-mysql") {
+        // MySQL does not support the `RETURNING` clause.
+        let mut connection = pool.acquire().await?;
+        let mut ctx = M::before_scan(sql).await?;
+        ctx.set_model_name(M::model_name());
+        let mut sqlx_query = sqlx::query(sql);
+        for value in arguments {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let query_result = sqlx_query.execute(&mut *connection).await?;
+        let rows_affected = query_result.rows_affected();
+        let success = rows_affected == 1;
+        ctx.set_query(sql);
+        ctx.set_query_result(Some(rows_affected), success);
+        M::after_scan(&ctx).await?;
+
+        let row = if success {
+            let primary_key_name = M::PRIMARY_KEY_NAME;
+            let table_name = M::qualified_table_name();
+            let tenant_condition = tenant::format_tenant_condition::<M>()?;
+            let sql = format!(
+                "SELECT {projection} FROM {table_name} \
+                    WHERE {primary_key_name} = {primary_key}{tenant_condition};"
+            );
+            sqlx::query(&sql).fetch_optional(&mut *connection).await?
+        } else {
+            None
+        };
+        Ok((ctx, row))
+    } else {
+        let sql = format!("{} RETURNING {projection};", sql.trim_end_matches(';'));
+        let mut ctx = M::before_scan(&sql).await?;
+        ctx.set_model_name(M::model_name());
+        let mut sqlx_query = sqlx::query(&sql);
+        for value in arguments {
+            sqlx_query = query::bind_argument(sqlx_query, value);
+            ctx.add_argument(value);
+        }
+        let row = sqlx_query.fetch_optional(pool).await?;
+        let rows_affected = u64::from(row.is_some());
+        ctx.set_query(sql);
+        ctx.set_query_result(Some(rows_affected), row.is_some());
+        M::after_scan(&ctx).await?;
+        Ok((ctx, row))
+    }
+}
+
+/// Returns the error for a version control conflict in optimistic locking.
+pub(super) fn version_conflict_error<M: Schema>() -> Error {
+    let model_name = M::model_name();
+    let message =
+        format!("409 Conflict: the model `{model_name}` has been modified by another request");
+    Error::new(message).with_status_code(409)
+}
+
+/// Returns `true` if the model has a `status` column which can hold the `Deleted` value.
+pub(super) fn has_deleted_status<M: Schema>() -> bool {
+    M::get_column("status").is_some_and(|col| {
+        let enum_values = col.enum_values();
+        enum_values.is_empty() || enum_values.contains(&"Deleted")
+    })
+}
+
+/// Returns the updates for logically deleting the models.
+fn soft_delete_updates<M: Schema>() -> Result<Map, Error> {
+    let mut updates = Map::new();
+    if has_deleted_status::<M>() {
+        updates.upsert("status", "Deleted");
+    }
+    if M::has_column("deleted_at") {
+        updates.upsert("deleted_at", DateTime::now().to_utc_timestamp());
+    }
+    if updates.is_empty() {
+        let model_name = M::model_name();
+        return Err(Error::new(format!(
+            "405 Method Not Allowed: the model `{model_name}` does not support soft deletes"
+        )));
+    }
+    Ok(updates)
+}
+
+/// Formats the column definition for the `CREATE TABLE` or `ADD COLUMN` statement.
+fn format_column_definition(col: &Column<'_>, primary_key_name: &str) -> String {
+    let column_name = col.name();
+    let column_type = col.column_type();
+    let mut column = format!("{column_name} {column_type}");
+    if column_name == primary_key_name {
+        column += " PRIMARY KEY";
+    } else if let Some(value) = col.default_value() {
+        if col.auto_increment() {
+            column += if cfg!(feature = "orm-mysql") {
+                " AUTO_INCREMENT"
+            } else {
+                " AUTOINCREMENT"
+            };
+        } else {
+            let value = col.format_value(value);
+            if cfg!(feature = "orm-sqlite") && value.contains('(') {
+                column = format!("{column} DEFAULT ({value})");
+            } else {
+                column = format!("{column} DEFAULT {value}");
+            }
+        }
+    } else if col.is_not_null() {
+        column += " NOT NULL";
+    }
+    if col.enum_type().is_some() && !cfg!(feature = "orm-postgres") {
+        let values = format_enum_values(col);
+        column = format!("{column} CHECK ({column_name} IN ({values}))");
+    }
+    column
+}
+
+/// Formats a statement to create the enum type of a column for PostgreSQL.
+/// The statement does nothing if the type has already been created.
+fn format_enum_type_statement(col: &Column<'_>) -> Option<String> {
+    if !cfg!(feature = "orm-postgres") {
+        return None;
+    }
+
+    let enum_type = col.enum_type()?;
+    let values = format_enum_values(col);
+    let sql = format!(
+        "DO $$ BEGIN CREATE TYPE {enum_type} AS ENUM ({values}); \
+            EXCEPTION WHEN duplicate_object THEN NULL; END $$;"
+    );
+    Some(sql)
+}
+
+/// Formats the allowed values of an enum column as a list of string literals.
+fn format_enum_values(col: &Column<'_>) -> String {
+    col.enum_values()
+        .iter()
+        .map(Query::escape_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats the `FOREIGN KEY` constraint for a scalar reference column.
+/// Array references are only checked by the application.
+fn format_foreign_key(col: &Column<'_>) -> Option<String> {
+    /// Referential actions.
+    const REFERENTIAL_ACTIONS: [&str; 5] =
+        ["CASCADE", "RESTRICT", "SET NULL", "SET DEFAULT", "NO ACTION"];
+
+    let reference = col.reference().filter(|r| r.is_foreign_key())?;
+    let column_name = col.name();
+    if col.type_name().starts_with("Vec<") {
+        tracing::warn!(column_name, "foreign keys are not supported for array columns");
+        return None;
+    }
+
+    let reference_name = tenant::qualify_table_name(reference.name());
+    let reference_column_name = reference.column_name();
+    let mut constraint = format!(
+        "FOREIGN KEY ({column_name}) REFERENCES {reference_name} ({reference_column_name})"
+    );
+    let actions = [("ON DELETE", reference.on_delete()), ("ON UPDATE", reference.on_update())];
+    for (clause, action) in actions {
+        if let Some(action) = action {
+            let action = action.replace('_', " ").to_uppercase();
+            if REFERENTIAL_ACTIONS.contains(&action.as_str()) {
+                constraint = format!("{constraint} {clause} {action}");
+            } else {
+                tracing::warn!(column_name, "invalid referential action `{action}`");
+            }
+        }
+    }
+    Some(constraint)
+}
+
+/// Formats the `CREATE INDEX` statements for the columns and composite indexes,
+/// and returns a list of the index names with the statements.
+fn format_index_statements(
+    table_name: &str,
+    columns: &[Column<'_>],
+    indexes: &[Index<'_>],
+) -> Vec<(String, String)> {
+    let mut statements = Vec::new();
+    let index_prefix = table_name
+        .rsplit_once('.')
+        .map_or(table_name, |(_, table_name)| table_name);
+    if cfg!(feature = "orm-mysql") {
+        let mut text_search_columns = Vec::new();
+        for col in columns {
+            if let Some(index_type) = col.index_type() {
+                let column_name = col.name();
+                let index_name = format!("{index_prefix}_{column_name}_index");
+                if matches!(index_type, "fulltext" | "text") {
+                    text_search_columns.push(column_name);
+                } else if matches!(index_type, "unique" | "spatial") {
+                    let index_type = index_type.to_uppercase();
+                    let sql = format!(
+                        "CREATE {index_type} INDEX {index_name} ON {table_name} ({column_name});"
+                    );
+                    statements.push((index_name, sql));
+                } else if matches!(index_type, "btree" | "hash") {
+                    let index_type = index_type.to_uppercase();
+                    let sql = format!(
+                        "CREATE INDEX {index_name} \
+                            ON {table_name} ({column_name}) USING {index_type};"
+                    );
+                    statements.push((index_name, sql));
+                }
+            }
+        }
+        if !text_search_columns.is_empty() {
+            let text_search_columns = text_search_columns.join(", ");
+            let index_name = format!("{index_prefix}_text_search_index");
+            let sql = format!(
+                "CREATE FULLTEXT INDEX {index_name} ON {table_name} ({text_search_columns});"
+            );
+            statements.push((index_name, sql));
+        }
+    } else if cfg!(feature = "orm-postgres") {
+        let mut text_search_columns = Vec::new();
+        let mut text_search_languages = Vec::new();
+        for col in columns {
+            if let Some(index_type) = col.index_type() {
+                let column_name = col.name();
+                let index_name = format!("{index_prefix}_{column_name}_index");
+                if index_type.starts_with("text") {
+                    let language = index_type.strip_prefix("text:").unwrap_or("english");
+                    let column = format!("coalesce({column_name}, '')");
+                    if !text_search_languages.contains(&language) {
+                        text_search_languages.push(language);
+                    }
+                    text_search_columns.push((language, column));
+                } else if index_type == "unique" {
+                    let sql = format!(
+                        "CREATE UNIQUE INDEX IF NOT EXISTS {index_name} \
+                            ON {table_name} ({column_name});"
+                    );
+                    statements.push((index_name, sql));
+                } else {
+                    let sort_order = if index_type == "btree" { " DESC" } else { "" };
+                    let sql = format!(
+                        "CREATE INDEX IF NOT EXISTS {index_name} \
+                            ON {table_name} USING {index_type}({column_name}{sort_order});"
+                    );
+                    statements.push((index_name, sql));
+                }
+            }
+        }
+        for language in text_search_languages {
+            let text = text_search_columns
+                .iter()
+                .filter_map(|col| (col.0 == language).then_some(col.1.as_str()))
+                .intersperse(" || ' ' || ")
+                .collect::<String>();
+            let text_search = format!("to_tsvector('{language}', {text})");
+            let index_name = format!("{index_prefix}_text_search_{language}_index");
+            let sql = format!(
+                "CREATE INDEX IF NOT EXISTS {index_name} \
+                    ON {table_name} USING gin({text_search});"
+            );
+            statements.push((index_name, sql));
+        }
+    } else {
+        for col in columns {
+            if let Some(index_type) = col.index_type() {
+                let column_name = col.name();
+                let index_name = format!("{index_prefix}_{column_name}_index");
+                let index_type = if index_type == "unique" { "UNIQUE" } else { "" };
+                let sql = format!(
+                    "CREATE {index_type} INDEX IF NOT EXISTS {index_name} \
+                        ON {table_name} ({column_name});"
+                );
+                statements.push((index_name, sql));
+            }
+        }
+    }
+    for index in indexes {
+        let index_name = index.index_name(index_prefix);
+        let index_columns = index.columns().join(", ");
+        let unique = if index.is_unique() { "UNIQUE " } else { "" };
+        let sql = if cfg!(feature = "orm-mysql") {
+            if index.predicate().is_some() {
+                // Creating the index without the predicate would widen the constraint.
+                tracing::error!(table_name, index_name, "partial indexes are not supported");
+                continue;
+            }
+            let index_type = index
+                .index_type()
+                .map(|index_type| format!(" USING {}", index_type.to_uppercase()))
+                .unwrap_or_default();
+            format!(
+                "CREATE {unique}INDEX {index_name} \
+                    ON {table_name} ({index_columns}){index_type};"
+            )
+        } else {
+            let index_type = if cfg!(feature = "orm-postgres") {
+                index
+                    .index_type()
+                    .map(|index_type| format!(" USING {index_type}"))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+            let predicate = index
+                .predicate()
+                .map(|predicate| format!(" WHERE {predicate}"))
+                .unwrap_or_default();
+            format!(
+                "CREATE {unique}INDEX IF NOT EXISTS {index_name} \
+                    ON {table_name}{index_type} ({index_columns}){predicate};"
+            )
+        };
+        statements.push((index_name, sql));
+    }
+    statements
+}
+
+/// Deduplicates the non-null keys of the related models.
+fn dedup_keys(values: Vec<JsonValue>) -> Vec<JsonValue> {
+    let mut keys = Vec::with_capacity(values.len());
+    let mut visited = HashSet::with_capacity(values.len());
+    for value in values {
+        if !value.is_null() && visited.insert(value.to_string()) {
+            keys.push(value);
+        }
+    }
+    keys
+}
+
+/// Associates the related models with the rows by matching the values of the `local_key`
+/// with the `foreign_key`. The related data is stored in the `relation` field as an object,
+/// or as a list of objects if `many` is `true`.
+fn associate_models(
+    data: &mut [Map],
+    associations: &[Map],
+    local_key: &str,
+    foreign_key: &str,
+    relation: &str,
+    many: bool,
+) {
+    let find_related = |key: &JsonValue| {
+        associations
+            .iter()
+            .filter(|model| model.get(foreign_key) == Some(key))
+            .map(|model| JsonValue::from(model.clone()))
+            .collect::<Vec<_>>()
+    };
+    for row in data.iter_mut() {
+        let related_value = match row.get(local_key) {
+            Some(JsonValue::Array(vec)) => vec.iter().flat_map(find_related).collect(),
+            Some(key) if many => find_related(key).into(),
+            Some(key) => find_related(key).into_iter().next().unwrap_or_default(),
+            None => continue,
+        };
+        row.upsert(relation, related_value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{associate_models, dedup_keys};
+    use crate::{extension::JsonObjectExt, model::Query, JsonValue, Map};
+
+    #[test]
+    fn it_dedups_related_keys() {
+        let values = vec!["u1".into(), "u2".into(), JsonValue::Null, "u1".into()];
+        let keys = dedup_keys(values);
+        assert_eq!(keys, vec![JsonValue::from("u1"), "u2".into()]);
+    }
+
+    #[test]
+    fn it_associates_included_models() {
+        let mut query = Query::default();
+        let validation = query.read_map(&Map::from_entry("include", "members"));
+        assert!(validation.is_success());
+        assert!(query.is_included("members"));
+
+        let mut group = Map::from_entry("id", "g1");
+        group.upsert("members", vec!["u1", "u2"]);
+        let mut data = vec![group];
+        let associations = ["u1", "u2", "u3"]
+            .into_iter()
+            .map(|id| Map::from_entry("id", id))
+            .collect::<Vec<_>>();
+        associate_models(&mut data, &associations, "members", "id", "members", true);
+
+        let members = data[0].get_array("members").unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].get("id").and_then(|v| v.as_str()), Some("u1"));
+        assert_eq!(members[1].get("id").and_then(|v| v.as_str()), Some("u2"));
+    }
+}
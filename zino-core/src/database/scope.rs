@@ -0,0 +1,48 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context, Poll},
+    thread::LocalKey,
+};
+
+/// Runs the future with the value as the context of the current scope.
+/// It does not depend on the async runtime since the context is restored on each poll.
+pub(super) async fn run<T, F>(
+    key: &'static LocalKey<RefCell<Option<T>>>,
+    value: T,
+    future: F,
+) -> F::Output
+where
+    T: Clone + Unpin + 'static,
+    F: Future,
+{
+    ScopedFuture {
+        key,
+        value,
+        future: pin!(future),
+    }
+    .await
+}
+
+/// A future running in the scope of a context value.
+struct ScopedFuture<'a, T: 'static, F> {
+    /// Thread-local key of the context.
+    key: &'static LocalKey<RefCell<Option<T>>>,
+    /// Context value.
+    value: T,
+    /// The inner future.
+    future: Pin<&'a mut F>,
+}
+
+impl<T: Clone + Unpin + 'static, F: Future> Future for ScopedFuture<'_, T, F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let value = self.value.clone();
+        let prev_value = self.key.with(|ctx| ctx.replace(Some(value)));
+        let output = self.future.as_mut().poll(cx);
+        self.key.with(|ctx| ctx.replace(prev_value));
+        output
+    }
+}
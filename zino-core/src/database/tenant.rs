@@ -0,0 +1,261 @@
+use super::{query::QueryExt, scope, Schema};
+use crate::{
+    error::Error,
+    extension::JsonObjectExt,
+    model::{Mutation, Query},
+    Map,
+};
+use std::{borrow::Cow, cell::RefCell, future::Future};
+
+/// Context of the tenant, which is shared by the model operations in a scope.
+///
+/// The models with a `TENANT_KEY_NAME` are isolated by the tenant of the current scope.
+/// Accessing them outside of a tenant scope is forbidden, and the cross-tenant access
+/// should be made explicitly in the scope of [`TenantContext::all_tenants()`].
+///
+/// For the physical isolation, the tenant can also be bound to a PostgreSQL schema
+/// which qualifies the table names, or to a dedicated connection pool.
+#[derive(Debug, Clone)]
+pub struct TenantContext {
+    /// Tenant ID. It is `None` for the cross-tenant access.
+    tenant_id: Option<String>,
+    /// Schema name of the tenant.
+    schema_name: Option<String>,
+    /// Name of the connection pool of the tenant.
+    pool_name: Option<String>,
+}
+
+impl TenantContext {
+    /// Creates a new instance for the tenant.
+    #[inline]
+    pub fn new(tenant_id: impl ToString) -> Self {
+        Self {
+            tenant_id: Some(tenant_id.to_string()),
+            schema_name: None,
+            pool_name: None,
+        }
+    }
+
+    /// Creates a new instance for the cross-tenant access.
+    #[inline]
+    pub fn all_tenants() -> Self {
+        Self {
+            tenant_id: None,
+            schema_name: None,
+            pool_name: None,
+        }
+    }
+
+    /// Sets the schema name of the tenant. The name is converted to lowercase,
+    /// and the characters other than ASCII alphanumerics and `_` are replaced with `_`,
+    /// so that it is the same whether it is quoted or not.
+    ///
+    /// It only takes effect for PostgreSQL.
+    pub fn set_schema_name(&mut self, schema_name: &str) {
+        let schema_name = schema_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        self.schema_name = (!schema_name.is_empty()).then_some(schema_name);
+    }
+
+    /// Sets the name of the connection pool of the tenant.
+    #[inline]
+    pub fn set_pool_name(&mut self, pool_name: impl ToString) {
+        self.pool_name = Some(pool_name.to_string());
+    }
+
+    /// Returns the tenant ID.
+    #[inline]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Returns the schema name of the tenant.
+    #[inline]
+    pub fn schema_name(&self) -> Option<&str> {
+        self.schema_name.as_deref()
+    }
+
+    /// Returns the name of the connection pool of the tenant.
+    #[inline]
+    pub fn pool_name(&self) -> Option<&str> {
+        self.pool_name.as_deref()
+    }
+
+    /// Returns `true` if it is in the context of the cross-tenant access.
+    #[inline]
+    pub fn is_cross_tenant(&self) -> bool {
+        self.tenant_id.is_none()
+    }
+
+    /// Returns the tenant context of the current scope.
+    #[inline]
+    pub fn current() -> Option<Self> {
+        CURRENT_TENANT_CONTEXT.with(|ctx| ctx.borrow().clone())
+    }
+
+    /// Runs the future with the tenant context as the context of the current scope.
+    #[inline]
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        scope::run(&CURRENT_TENANT_CONTEXT, self, future).await
+    }
+
+    /// Provisions the table and indexes of the model for the tenant.
+    /// The schema of the tenant is created if it does not exist.
+    pub async fn provision<M: Schema>(&self) -> Result<(), Error> {
+        self.clone()
+            .scope(async {
+                if cfg!(feature = "orm-postgres")
+                    && let Some(schema_name) = self.schema_name()
+                {
+                    let pool = M::init_writer()?.pool();
+                    let sql = format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema_name}";"#);
+                    sqlx::query(&sql).execute(pool).await?;
+                }
+                M::create_table().await?;
+                M::create_indexes().await?;
+                Ok(())
+            })
+            .await
+    }
+}
+
+/// Returns the schema name of the tenant in the current scope.
+pub(super) fn current_schema_name() -> Option<String> {
+    if cfg!(feature = "orm-postgres") {
+        CURRENT_TENANT_CONTEXT.with(|ctx| {
+            ctx.borrow()
+                .as_ref()
+                .and_then(|ctx| ctx.schema_name.clone())
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the name of the connection pool of the tenant in the current scope.
+pub(super) fn current_pool_name() -> Option<String> {
+    CURRENT_TENANT_CONTEXT.with(|ctx| ctx.borrow().as_ref().and_then(|ctx| ctx.pool_name.clone()))
+}
+
+/// Qualifies the table name by the schema of the tenant in the current scope.
+/// A table name which has already been qualified is kept as it is.
+pub(super) fn qualify_table_name(table_name: &str) -> Cow<'_, str> {
+    match current_schema_name() {
+        Some(schema_name) if !table_name.contains('.') => {
+            Cow::Owned(format!("{schema_name}.{table_name}"))
+        }
+        _ => Cow::Borrowed(table_name),
+    }
+}
+
+/// Returns the tenant ID of the current scope for the model.
+/// It is `None` if the model is not isolated by tenants or in the cross-tenant scope.
+pub(super) fn current_tenant_id<M: Schema>() -> Result<Option<String>, Error> {
+    let Some(tenant_id_field) = M::TENANT_KEY_NAME else {
+        return Ok(None);
+    };
+    match TenantContext::current() {
+        Some(ctx) => Ok(ctx.tenant_id),
+        None => {
+            let model_name = M::model_name();
+            Err(Error::new(format!(
+                "403 Forbidden: the model `{model_name}` can only be accessed \
+                    in the scope of a tenant by the `{tenant_id_field}` field"
+            )))
+        }
+    }
+}
+
+/// Checks whether the model can be accessed in the current scope.
+#[inline]
+pub(super) fn check_access<M: Schema>() -> Result<(), Error> {
+    current_tenant_id::<M>().map(|_| ())
+}
+
+/// Checks whether the model can be accessed in the current scope,
+/// and pins the tenant of the models to be updated by the mutation.
+pub(super) fn check_mutation<M: Schema>(mutation: &mut Mutation) -> Result<(), Error> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(tenant_id) = current_tenant_id::<M>()?
+    {
+        mutation.add_update(tenant_id_field, tenant_id);
+    }
+    Ok(())
+}
+
+/// Adds the tenant predicate to the filters for the model.
+/// The filters are left unchanged outside of a tenant scope, in which case
+/// the access has been rejected by [`current_tenant_id()`].
+pub(super) fn tenant_filters<M: Schema>(mut filters: Cow<'_, Map>) -> Cow<'_, Map> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(ctx) = TenantContext::current()
+        && let Some(tenant_id) = ctx.tenant_id
+    {
+        filters.to_mut().upsert(tenant_id_field, tenant_id);
+    }
+    filters
+}
+
+/// Returns the tenant predicate of the model in which the field is qualified by the model name,
+/// which can be used in the queries joining multiple tables.
+pub(super) fn qualified_tenant_filter<M: Schema>() -> Result<Option<(String, String)>, Error> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(tenant_id) = current_tenant_id::<M>()?
+    {
+        let model_name = M::model_name();
+        Ok(Some((format!("{model_name}.{tenant_id_field}"), tenant_id)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Stamps the tenant of the current scope on the model data.
+pub(super) fn stamp_tenant<M: Schema>(data: &mut Map) -> Result<(), Error> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(tenant_id) = current_tenant_id::<M>()?
+    {
+        data.upsert(tenant_id_field, tenant_id);
+    }
+    Ok(())
+}
+
+/// Formats the tenant condition to be appended to the `WHERE` clause for the model.
+pub(super) fn format_tenant_condition<M: Schema>() -> Result<String, Error> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(tenant_id) = current_tenant_id::<M>()?
+    {
+        let field = Query::format_field(tenant_id_field);
+        let tenant_id = Query::escape_string(tenant_id);
+        Ok(format!(" AND {field} = {tenant_id}"))
+    } else {
+        Ok(String::new())
+    }
+}
+
+/// Formats the tenant condition of the conflict update in an upsert statement,
+/// so that the rows of the other tenants are never updated.
+pub(super) fn format_upsert_condition<M: Schema>() -> Result<Option<String>, Error> {
+    if let Some(tenant_id_field) = M::TENANT_KEY_NAME
+        && let Some(tenant_id) = current_tenant_id::<M>()?
+    {
+        let table_name = M::qualified_table_name();
+        let field = Query::format_field(tenant_id_field);
+        let tenant_id = Query::escape_string(tenant_id);
+        Ok(Some(format!("{table_name}.{field} = {tenant_id}")))
+    } else {
+        Ok(None)
+    }
+}
+
+thread_local! {
+    /// Tenant context of the current scope.
+    static CURRENT_TENANT_CONTEXT: RefCell<Option<TenantContext>> = const { RefCell::new(None) };
+}
@@ -0,0 +1,222 @@
+use super::{schema, scope, DatabaseDriver, DatabaseRow, Schema};
+use crate::{
+    error::Error,
+    model::{DecodeRow, Mutation, Query, QueryContext},
+    BoxFuture,
+};
+use parking_lot::Mutex;
+use sqlx::{Acquire, Transaction};
+use std::{any::TypeId, cell::RefCell, mem, sync::Arc};
+
+/// Tasks to be run after the transaction is committed.
+type DeferredTasks = Arc<Mutex<Vec<BoxFuture<'static, Result<(), Error>>>>>;
+
+/// A transaction in which the model operations run on the same database connection.
+///
+/// The model hooks are called as usual, and [`TransactionContext::in_scope()`] returns `true`
+/// inside them. Nested transactions are supported by savepoints.
+/// If the transaction is dropped without being committed, it will be rolled back.
+pub struct TransactionContext<'c> {
+    /// The underlying transaction.
+    tx: Transaction<'c, DatabaseDriver>,
+    /// Models whose query cache should be invalidated when the transaction is committed.
+    touched_models: Vec<(TypeId, fn())>,
+    /// Touched models of the parent transaction for a savepoint.
+    parent_touched_models: Option<&'c mut Vec<(TypeId, fn())>>,
+    /// Tasks deferred by the model hooks until the transaction is committed.
+    deferred_tasks: DeferredTasks,
+    /// Deferred tasks of the parent transaction for a savepoint.
+    parent_deferred_tasks: Option<DeferredTasks>,
+}
+
+impl<'c> TransactionContext<'c> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(tx: Transaction<'c, DatabaseDriver>) -> Self {
+        Self {
+            tx,
+            touched_models: Vec::new(),
+            parent_touched_models: None,
+            deferred_tasks: DeferredTasks::default(),
+            parent_deferred_tasks: None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying transaction.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut Transaction<'c, DatabaseDriver> {
+        &mut self.tx
+    }
+
+    /// Begins a nested transaction by creating a savepoint.
+    pub async fn begin(&mut self) -> Result<TransactionContext<'_>, Error> {
+        let tx = (&mut self.tx).begin().await?;
+        Ok(TransactionContext {
+            tx,
+            touched_models: Vec::new(),
+            parent_touched_models: Some(&mut self.touched_models),
+            deferred_tasks: DeferredTasks::default(),
+            parent_deferred_tasks: Some(self.deferred_tasks.clone()),
+        })
+    }
+
+    /// Commits the transaction, or releases the savepoint for a nested transaction.
+    ///
+    /// The query cache of the models changed in the transaction is invalidated
+    /// and the tasks deferred by the model hooks are run after the commit,
+    /// or they are deferred to the parent transaction for a savepoint.
+    pub async fn commit(self) -> Result<(), Error> {
+        let Self {
+            tx,
+            touched_models,
+            parent_touched_models,
+            deferred_tasks,
+            parent_deferred_tasks,
+        } = self;
+        tx.commit().await?;
+
+        let tasks = mem::take(&mut *deferred_tasks.lock());
+        if let Some(parent_touched_models) = parent_touched_models {
+            for (type_id, invalidate) in touched_models {
+                if !parent_touched_models.iter().any(|&(id, _)| id == type_id) {
+                    parent_touched_models.push((type_id, invalidate));
+                }
+            }
+        } else {
+            for (_, invalidate) in touched_models {
+                invalidate();
+            }
+        }
+        if let Some(parent_deferred_tasks) = parent_deferred_tasks {
+            parent_deferred_tasks.lock().extend(tasks);
+        } else {
+            for task in tasks {
+                if let Err(err) = task.await {
+                    tracing::error!("fail to run a deferred task of the transaction: {err}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls back the transaction, or rolls back to the savepoint for a nested transaction.
+    pub async fn rollback(self) -> Result<(), Error> {
+        self.tx.rollback().await.map_err(Error::from)
+    }
+
+    /// Executes the specific operations inside a savepoint.
+    /// If the operations return an error, it will be rolled back to the savepoint;
+    /// if not, the savepoint will be released.
+    pub async fn savepoint<F, T>(&mut self, tx: F) -> Result<T, Error>
+    where
+        F: for<'a, 't> FnOnce(&'a mut TransactionContext<'t>) -> BoxFuture<'a, Result<T, Error>>,
+    {
+        let mut savepoint = self.begin().await?;
+        let data = tx(&mut savepoint).await?;
+        savepoint.commit().await?;
+        Ok(data)
+    }
+
+    /// Inserts the model into the table.
+    pub async fn insert<M: Schema>(&mut self, model: M) -> Result<QueryContext, Error> {
+        let future = schema::execute_insert(&mut *self.tx, model);
+        let result = scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await;
+        self.touch::<M>();
+        result
+    }
+
+    /// Updates the model in the table.
+    pub async fn update<M: Schema>(&mut self, model: M) -> Result<QueryContext, Error> {
+        let future = schema::execute_update(&mut *self.tx, model);
+        let result = scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await;
+        self.touch::<M>();
+        result
+    }
+
+    /// Updates many models selected by the query in the table.
+    pub async fn update_many<M: Schema>(
+        &mut self,
+        query: &Query,
+        mutation: &mut Mutation,
+    ) -> Result<u64, Error> {
+        let future = schema::execute_update_many::<M, _>(&mut *self.tx, query, mutation);
+        let result = scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await;
+        self.touch::<M>();
+        result
+    }
+
+    /// Deletes the model in the table.
+    pub async fn delete<M: Schema>(&mut self, model: M) -> Result<QueryContext, Error> {
+        let future = schema::execute_delete(&mut *self.tx, model);
+        let result = scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await;
+        self.touch::<M>();
+        result
+    }
+
+    /// Deletes many models selected by the query in the table.
+    pub async fn delete_many<M: Schema>(&mut self, query: &Query) -> Result<u64, Error> {
+        let future = schema::execute_delete_many::<M, _>(&mut *self.tx, query);
+        let result = scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await;
+        self.touch::<M>();
+        result
+    }
+
+    /// Finds a list of models selected by the query in the table,
+    /// and decodes it as `Vec<T>`.
+    pub async fn find<M: Schema, T: DecodeRow<DatabaseRow, Error = Error>>(
+        &mut self,
+        query: &Query,
+    ) -> Result<Vec<T>, Error> {
+        let future = schema::execute_find::<M, T, _>(&mut *self.tx, query);
+        scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await
+    }
+
+    /// Finds one model selected by the query in the table,
+    /// and decodes it as an instance of type `T`.
+    pub async fn find_one<M: Schema, T: DecodeRow<DatabaseRow, Error = Error>>(
+        &mut self,
+        query: &Query,
+    ) -> Result<Option<T>, Error> {
+        let future = schema::execute_find_one::<M, T, _>(&mut *self.tx, query);
+        scope::run(&IN_TRANSACTION, self.deferred_tasks.clone(), future).await
+    }
+
+    /// Defers the task until the transaction of the current scope is committed.
+    /// The task is dropped if the transaction is rolled back.
+    /// It returns the task back if the current scope is not an operation of a transaction.
+    pub(super) fn defer(
+        task: BoxFuture<'static, Result<(), Error>>,
+    ) -> Option<BoxFuture<'static, Result<(), Error>>> {
+        IN_TRANSACTION.with(|ctx| match ctx.borrow().as_ref() {
+            Some(deferred_tasks) => {
+                deferred_tasks.lock().push(task);
+                None
+            }
+            None => Some(task),
+        })
+    }
+
+    /// Records the model whose query cache should be invalidated after the commit.
+    fn touch<M: Schema>(&mut self) {
+        let type_id = TypeId::of::<M>();
+        if !self.touched_models.iter().any(|&(id, _)| id == type_id) {
+            let invalidate: fn() = M::invalidate_query_cache;
+            self.touched_models.push((type_id, invalidate));
+        }
+    }
+
+    /// Returns `true` if the current scope is an operation of a transaction.
+    ///
+    /// The model hooks run on the connection pools instead of the transaction,
+    /// so they can check it to skip the side effects which should not be visible
+    /// outside of the transaction.
+    #[inline]
+    pub fn in_scope() -> bool {
+        IN_TRANSACTION.with(|ctx| ctx.borrow().is_some())
+    }
+}
+
+thread_local! {
+    /// Deferred tasks of the transaction if the current scope is an operation of it.
+    static IN_TRANSACTION: RefCell<Option<DeferredTasks>> = const { RefCell::new(None) };
+}
@@ -1,5 +1,8 @@
 //! Base64 encoding and decoding.
-use base64::{engine::general_purpose::STANDARD_NO_PAD, DecodeError, Engine};
+use base64::{
+    engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+    DecodeError, Engine,
+};
 
 /// Encodes the data as base64 string.
 #[inline]
@@ -13,6 +16,18 @@ pub(crate) fn decode(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
     STANDARD_NO_PAD.decode(data)
 }
 
+/// Encodes the data as URL-safe base64 string.
+#[inline]
+pub(crate) fn encode_url_safe(data: impl AsRef<[u8]>) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Decodes the URL-safe base64-encoded data as `Vec<u8>`.
+#[inline]
+pub(crate) fn decode_url_safe(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
+    URL_SAFE_NO_PAD.decode(data)
+}
+
 /// Encodes the data as base64-encoded data URL string.
 #[cfg(feature = "connector-arrow")]
 pub(crate) fn encode_data_url(data: impl AsRef<[u8]>) -> String {
@@ -13,6 +13,8 @@ pub struct Error {
     message: SharedString,
     /// Error source.
     source: Option<Box<Error>>,
+    /// Status code for the error.
+    status_code: Option<u16>,
 }
 
 impl Error {
@@ -22,6 +24,7 @@ impl Error {
         Self {
             message: message.into(),
             source: None,
+            status_code: None,
         }
     }
 
@@ -31,24 +34,40 @@ impl Error {
         Self {
             message: message.into(),
             source: Some(Box::new(source.into())),
+            status_code: None,
         }
     }
 
     /// Wraps the error value with additional contextual message.
     #[inline]
     pub fn context(self, message: impl Into<SharedString>) -> Self {
+        let status_code = self.status_code;
         Self {
             message: message.into(),
             source: Some(Box::new(self)),
+            status_code,
         }
     }
 
+    /// Sets the status code for the error.
+    #[inline]
+    pub fn with_status_code(mut self, status_code: u16) -> Self {
+        self.status_code = Some(status_code);
+        self
+    }
+
     /// Returns the error message.
     #[inline]
     pub fn message(&self) -> &str {
         self.message.as_ref()
     }
 
+    /// Returns the status code for the error.
+    #[inline]
+    pub fn status_code(&self) -> Option<u16> {
+        self.status_code
+    }
+
     /// Returns the error source.
     #[inline]
     pub fn source(&self) -> Option<&Error> {
@@ -76,6 +95,7 @@ impl<E: error::Error> From<E> for Error {
         Self {
             message: err.to_string().into(),
             source: err.source().map(|err| Box::new(Self::new(err.to_string()))),
+            status_code: None,
         }
     }
 }
@@ -2,6 +2,7 @@ use crate::{
     datetime::{self, DateTime},
     helper, openapi, JsonValue, Map, Record, Uuid,
 };
+use serde::de::DeserializeOwned;
 use std::{
     borrow::Cow,
     net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr},
@@ -133,6 +134,12 @@ pub trait JsonObjectExt {
     /// Extracts the string corresponding to the key and parses it as `Ipv6Addr`.
     fn parse_ipv6(&self, key: &str) -> Option<Result<Ipv6Addr, AddrParseError>>;
 
+    /// Extracts the value corresponding to the key and deserializes it as `T`.
+    fn deserialize_value<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Option<Result<T, serde_json::Error>>;
+
     /// Looks up a value by a JSON Pointer.
     ///
     /// A Pointer is a Unicode string with the reference tokens separated by `/`.
@@ -422,6 +429,14 @@ impl JsonObjectExt for Map {
         self.get_str(key).map(|s| s.parse())
     }
 
+    #[inline]
+    fn deserialize_value<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Option<Result<T, serde_json::Error>> {
+        self.get(key).map(T::deserialize)
+    }
+
     fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
         let Some(path) = pointer.strip_prefix('/') else {
             return None;
@@ -505,6 +520,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_deserializes_number_array() {
+        let mut map = Map::new();
+        map.upsert("scores", vec![1, 2]);
+
+        assert_eq!(map.parse_str_array("scores"), None);
+        assert_eq!(
+            map.deserialize_value::<Vec<i64>>("scores")
+                .and_then(|result| result.ok()),
+            Some(vec![1, 2])
+        );
+        assert!(map
+            .deserialize_value::<Vec<String>>("scores")
+            .is_some_and(|result| result.is_err()));
+    }
+
     #[test]
     fn it_lookups_json_value() {
         let mut map = Map::new();
@@ -0,0 +1,207 @@
+use crate::{extension::JsonValueExt, request::Validation, Map};
+
+/// Aggregate functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFunction {
+    /// Number of rows or non-null values.
+    Count,
+    /// Number of distinct values.
+    CountDistinct,
+    /// Sum of values.
+    Sum,
+    /// Average of values.
+    Avg,
+    /// Minimum value.
+    Min,
+    /// Maximum value.
+    Max,
+}
+
+impl AggregateFunction {
+    /// Returns the name of the aggregate function, which is used as the suffix of the alias.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::CountDistinct => "count_distinct",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+}
+
+/// Time units for the date-bucketed grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateBucket {
+    /// Bucketed by year, e.g. `2023`.
+    Year,
+    /// Bucketed by month, e.g. `2023-08`.
+    Month,
+    /// Bucketed by day, e.g. `2023-08-01`.
+    Day,
+    /// Bucketed by hour, e.g. `2023-08-01 10:00`.
+    Hour,
+}
+
+impl DateBucket {
+    /// Parses the time unit.
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "year" => Some(Self::Year),
+            "month" => Some(Self::Month),
+            "day" => Some(Self::Day),
+            "hour" => Some(Self::Hour),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the time unit.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Year => "year",
+            Self::Month => "month",
+            Self::Day => "day",
+            Self::Hour => "hour",
+        }
+    }
+}
+
+/// An aggregation of models.
+#[derive(Debug, Clone, Default)]
+pub struct Aggregation {
+    // Aggregate expressions of the function and the field.
+    aggregates: Vec<(AggregateFunction, String)>,
+    // Grouping fields with an optional date bucket.
+    groups: Vec<(String, Option<DateBucket>)>,
+}
+
+impl Aggregation {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the aggregation using the json object and returns the validation result.
+    /// The fields of the aggregate functions are specified by the keys
+    /// `count`, `count_distinct`, `sum`, `avg`, `min` and `max`,
+    /// and the grouping fields are specified by `group_by` and `bucket`,
+    /// where the latter has the format `{field}|{unit}` such as `created_at|month`.
+    pub fn read_map(&mut self, data: &Map) -> Validation {
+        let mut validation = Validation::new();
+        for (key, value) in data {
+            match key.as_str() {
+                "count" | "count_distinct" | "sum" | "avg" | "min" | "max" => {
+                    let function = match key.as_str() {
+                        "count" => AggregateFunction::Count,
+                        "count_distinct" => AggregateFunction::CountDistinct,
+                        "sum" => AggregateFunction::Sum,
+                        "avg" => AggregateFunction::Avg,
+                        "min" => AggregateFunction::Min,
+                        _ => AggregateFunction::Max,
+                    };
+                    if let Some(fields) = value.parse_str_array() {
+                        for field in fields {
+                            self.aggregates.push((function, field.to_owned()));
+                        }
+                    }
+                }
+                "group_by" => {
+                    if let Some(fields) = value.parse_str_array() {
+                        for field in fields {
+                            self.groups.push((field.to_owned(), None));
+                        }
+                    }
+                }
+                "bucket" => {
+                    if let Some(fields) = value.parse_str_array() {
+                        for field in fields {
+                            if let Some((field, unit)) = field.split_once('|')
+                                && let Some(bucket) = DateBucket::parse(unit)
+                            {
+                                self.groups.push((field.to_owned(), Some(bucket)));
+                            } else {
+                                validation.record("bucket", "invalid value for the date bucket");
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        validation
+    }
+
+    /// Adds an aggregate expression.
+    #[inline]
+    pub fn aggregate(mut self, function: AggregateFunction, field: impl Into<String>) -> Self {
+        self.aggregates.push((function, field.into()));
+        self
+    }
+
+    /// Counts the number of non-null values of the field,
+    /// or the number of rows if the field is `*`.
+    #[inline]
+    pub fn count(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::Count, field)
+    }
+
+    /// Counts the number of distinct values of the field.
+    #[inline]
+    pub fn count_distinct(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::CountDistinct, field)
+    }
+
+    /// Computes the sum of values of the field.
+    #[inline]
+    pub fn sum(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::Sum, field)
+    }
+
+    /// Computes the average of values of the field.
+    #[inline]
+    pub fn avg(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::Avg, field)
+    }
+
+    /// Computes the minimum value of the field.
+    #[inline]
+    pub fn min(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::Min, field)
+    }
+
+    /// Computes the maximum value of the field.
+    #[inline]
+    pub fn max(self, field: impl Into<String>) -> Self {
+        self.aggregate(AggregateFunction::Max, field)
+    }
+
+    /// Groups the rows by the field.
+    #[inline]
+    pub fn group_by(mut self, field: impl Into<String>) -> Self {
+        self.groups.push((field.into(), None));
+        self
+    }
+
+    /// Groups the rows by the date bucket of the field.
+    #[inline]
+    pub fn group_by_date(mut self, field: impl Into<String>, bucket: DateBucket) -> Self {
+        self.groups.push((field.into(), Some(bucket)));
+        self
+    }
+
+    /// Returns a reference to the aggregate expressions.
+    #[inline]
+    pub fn aggregates(&self) -> &[(AggregateFunction, String)] {
+        &self.aggregates
+    }
+
+    /// Returns a reference to the grouping fields.
+    #[inline]
+    pub fn groups(&self) -> &[(String, Option<DateBucket>)] {
+        &self.groups
+    }
+}
@@ -1,6 +1,6 @@
 use super::Reference;
 use crate::JsonValue;
-use apache_avro::schema::{Name, RecordField, RecordFieldOrder, Schema};
+use apache_avro::schema::{EnumSchema, Name, RecordField, RecordFieldOrder, Schema};
 use serde::Serialize;
 use std::{borrow::Cow, collections::BTreeMap};
 
@@ -22,6 +22,26 @@ pub struct Column<'a> {
     /// Reference.
     #[serde(skip_serializing_if = "Option::is_none")]
     reference: Option<Reference<'a>>,
+    /// A flag which indicates whether the column value is required.
+    required: bool,
+    /// Format of the column value, such as `email`, `uri` or `uuid`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a str>,
+    /// Regex pattern of the column value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<&'a str>,
+    /// Minimum of the column value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minimum: Option<f64>,
+    /// Maximum of the column value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maximum: Option<f64>,
+    /// Allowed values of the column.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    enum_values: &'a [&'a str],
+    /// Name of the enum type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enum_type: Option<&'a str>,
 }
 
 impl<'a> Column<'a> {
@@ -34,6 +54,13 @@ impl<'a> Column<'a> {
             default_value: None,
             index_type: None,
             reference: None,
+            required: false,
+            format: None,
+            pattern: None,
+            minimum: None,
+            maximum: None,
+            enum_values: &[],
+            enum_type: None,
         }
     }
 
@@ -55,6 +82,49 @@ impl<'a> Column<'a> {
         self.reference = Some(reference);
     }
 
+    /// Sets the `required` flag.
+    #[inline]
+    pub fn set_required(&mut self, required: bool) {
+        self.required = required;
+    }
+
+    /// Sets the format.
+    #[inline]
+    pub fn set_format(&mut self, format: &'a str) {
+        self.format = (!format.is_empty()).then_some(format);
+    }
+
+    /// Sets the regex pattern.
+    #[inline]
+    pub fn set_pattern(&mut self, pattern: &'a str) {
+        self.pattern = (!pattern.is_empty()).then_some(pattern);
+    }
+
+    /// Sets the minimum.
+    #[inline]
+    pub fn set_minimum(&mut self, minimum: f64) {
+        self.minimum = Some(minimum);
+    }
+
+    /// Sets the maximum.
+    #[inline]
+    pub fn set_maximum(&mut self, maximum: f64) {
+        self.maximum = Some(maximum);
+    }
+
+    /// Sets the allowed values.
+    #[inline]
+    pub fn set_enum_values(&mut self, enum_values: &'a [&'a str]) {
+        self.enum_values = enum_values;
+    }
+
+    /// Sets the enum type and the allowed values.
+    #[inline]
+    pub fn set_enum_type(&mut self, enum_type: &'a str, enum_values: &'a [&'a str]) {
+        self.enum_type = (!enum_type.is_empty()).then_some(enum_type);
+        self.enum_values = enum_values;
+    }
+
     /// Returns the name.
     #[inline]
     pub fn name(&self) -> &'a str {
@@ -67,6 +137,40 @@ impl<'a> Column<'a> {
         self.type_name
     }
 
+    /// Returns `true` if the column has a numeric type.
+    #[inline]
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.type_name,
+            "u64"
+                | "i64"
+                | "u32"
+                | "i32"
+                | "u16"
+                | "i16"
+                | "u8"
+                | "i8"
+                | "usize"
+                | "isize"
+                | "f64"
+                | "f32"
+                | "Decimal"
+                | "Option<u64>"
+                | "Option<i64>"
+                | "Option<u32>"
+                | "Option<i32>"
+        )
+    }
+
+    /// Returns `true` if the column has a date or time type.
+    #[inline]
+    pub fn is_datetime(&self) -> bool {
+        matches!(
+            self.type_name,
+            "DateTime" | "Date" | "Time" | "NaiveDateTime" | "NaiveDate" | "NaiveTime"
+        )
+    }
+
     /// Returns `true` if the column can not be null.
     #[inline]
     pub fn is_not_null(&self) -> bool {
@@ -98,8 +202,65 @@ impl<'a> Column<'a> {
         self.reference.as_ref()
     }
 
+    /// Returns `true` if the column value is required.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Returns the format.
+    #[inline]
+    pub fn format(&self) -> Option<&'a str> {
+        self.format
+    }
+
+    /// Returns the regex pattern.
+    #[inline]
+    pub fn pattern(&self) -> Option<&'a str> {
+        self.pattern
+    }
+
+    /// Returns the minimum.
+    #[inline]
+    pub fn minimum(&self) -> Option<f64> {
+        self.minimum
+    }
+
+    /// Returns the maximum.
+    #[inline]
+    pub fn maximum(&self) -> Option<f64> {
+        self.maximum
+    }
+
+    /// Returns the allowed values.
+    #[inline]
+    pub fn enum_values(&self) -> &'a [&'a str] {
+        self.enum_values
+    }
+
+    /// Returns the name of the enum type.
+    #[inline]
+    pub fn enum_type(&self) -> Option<&'a str> {
+        self.enum_type
+    }
+
     /// Returns the [Avro schema](apache_avro::schema::Schema).
     pub fn schema(&self) -> Schema {
+        if let Some(enum_type) = self.enum_type {
+            let symbols = self.enum_values.iter().map(|&s| s.to_owned()).collect();
+            return Schema::Enum(EnumSchema {
+                name: Name {
+                    name: enum_type.to_owned(),
+                    namespace: None,
+                },
+                aliases: None,
+                doc: None,
+                symbols,
+                default: None,
+                attributes: BTreeMap::new(),
+            });
+        }
+
         let type_name = self.type_name;
         match type_name {
             "bool" => Schema::Boolean,
@@ -139,6 +300,24 @@ impl<'a> Column<'a> {
             Schema::Double => s.parse::<f64>().ok().map(|f| f.into()),
             _ => Some(s.into()),
         });
+
+        // Validation rules are kept as the custom attributes.
+        let mut custom_attributes = BTreeMap::new();
+        if let Some(format) = self.format() {
+            custom_attributes.insert("format".to_owned(), format.into());
+        }
+        if let Some(pattern) = self.pattern() {
+            custom_attributes.insert("pattern".to_owned(), pattern.into());
+        }
+        if let Some(minimum) = self.minimum() {
+            custom_attributes.insert("minimum".to_owned(), minimum.into());
+        }
+        if let Some(maximum) = self.maximum() {
+            custom_attributes.insert("maximum".to_owned(), maximum.into());
+        }
+        if !self.enum_values.is_empty() && self.enum_type.is_none() {
+            custom_attributes.insert("enum".to_owned(), self.enum_values.into());
+        }
         RecordField {
             name: self.name().to_owned(),
             doc: None,
@@ -147,7 +326,7 @@ impl<'a> Column<'a> {
             schema,
             order: RecordFieldOrder::Ascending,
             position: 0,
-            custom_attributes: BTreeMap::new(),
+            custom_attributes,
         }
     }
 }
@@ -1,5 +1,8 @@
-use crate::{SharedString, Uuid};
-use std::time::Instant;
+use crate::{extension::TomlTableExt, state::State, SharedString, Uuid};
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
 
 /// Data associated with a query.
 #[derive(Debug, Clone)]
@@ -8,6 +11,10 @@ pub struct QueryContext {
     start_time: Instant,
     /// Query ID.
     query_id: Uuid,
+    /// Request ID.
+    request_id: Option<Uuid>,
+    /// Model name.
+    model_name: &'static str,
     /// A query.
     query: String,
     /// Arguments.
@@ -25,6 +32,8 @@ impl QueryContext {
         Self {
             start_time: Instant::now(),
             query_id: Uuid::new_v4(),
+            request_id: current_request_id(),
+            model_name: "",
             query: String::new(),
             arguments: Vec::new(),
             rows_affected: None,
@@ -32,6 +41,12 @@ impl QueryContext {
         }
     }
 
+    /// Sets the model name.
+    #[inline]
+    pub fn set_model_name(&mut self, model_name: &'static str) {
+        self.model_name = model_name;
+    }
+
     /// Sets the query.
     #[inline]
     pub fn set_query(&mut self, query: impl ToString) {
@@ -69,6 +84,18 @@ impl QueryContext {
         self.query_id
     }
 
+    /// Returns the request ID.
+    #[inline]
+    pub fn request_id(&self) -> Option<Uuid> {
+        self.request_id
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model_name(&self) -> &'static str {
+        self.model_name
+    }
+
     /// Returns the query.
     #[inline]
     pub fn query(&self) -> &str {
@@ -93,6 +120,14 @@ impl QueryContext {
         self.success
     }
 
+    /// Returns `true` if the execution time exceeds the threshold of slow queries,
+    /// which can be configured by the `slow-query-threshold` field of the `database` table.
+    /// It is always `false` if the threshold has not been configured.
+    #[inline]
+    pub fn is_slow_query(&self) -> bool {
+        SLOW_QUERY_THRESHOLD.is_some_and(|threshold| self.start_time.elapsed() > threshold)
+    }
+
     /// Formats the query arguments.
     #[inline]
     pub fn format_arguments(&self) -> String {
@@ -108,14 +143,31 @@ impl QueryContext {
         tracing::error!(query_id, query, arguments, message = message.as_ref());
     }
 
-    /// Emits the metrics for the query.
-    #[inline]
+    /// Emits the latency histogram and rows-affected counter for the query by model and action,
+    /// and records the execution time in the query statistics of the current scope.
     pub fn emit_metrics(&self, action: impl Into<SharedString>) {
+        let execution_time = self.start_time().elapsed();
+        let model_name = self.model_name();
+        let action = action.into();
         metrics::histogram!(
             "zino_model_query_duration_seconds",
-            self.start_time().elapsed().as_secs_f64(),
-            "action" => action.into(),
+            execution_time.as_secs_f64(),
+            "model" => model_name,
+            "action" => action.clone(),
         );
+        if let Some(rows_affected) = self.rows_affected() {
+            metrics::counter!(
+                "zino_model_query_rows_total",
+                rows_affected,
+                "model" => model_name,
+                "action" => action,
+            );
+        }
+
+        #[cfg(feature = "orm")]
+        if let Some(stats) = crate::database::QueryStats::current() {
+            stats.record(execution_time);
+        }
     }
 }
 
@@ -125,3 +177,23 @@ impl Default for QueryContext {
         Self::new()
     }
 }
+
+/// Returns the request ID of the current scope.
+#[inline]
+fn current_request_id() -> Option<Uuid> {
+    #[cfg(feature = "orm")]
+    {
+        crate::database::QueryStats::current().map(|stats| stats.request_id())
+    }
+    #[cfg(not(feature = "orm"))]
+    {
+        None
+    }
+}
+
+/// Threshold of the execution time for slow queries.
+static SLOW_QUERY_THRESHOLD: LazyLock<Option<Duration>> = LazyLock::new(|| {
+    State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_duration("slow-query-threshold"))
+});
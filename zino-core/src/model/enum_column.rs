@@ -0,0 +1,20 @@
+use crate::error::Error;
+
+/// An enum type which is stored as a column with a fixed set of values.
+///
+/// It is mapped to a native `ENUM` type for PostgreSQL,
+/// and a text column with the `CHECK` constraint for MySQL and SQLite.
+/// The trait can be derived with `#[derive(EnumColumn)]` from `zino-derive`.
+pub trait EnumColumn: Sized {
+    /// Name of the enum type in the database.
+    const TYPE_NAME: &'static str;
+
+    /// Column values of the variants.
+    const VARIANTS: &'static [&'static str];
+
+    /// Returns the column value of the variant.
+    fn as_column_value(&self) -> &'static str;
+
+    /// Parses the column value as a variant.
+    fn from_column_value(value: &str) -> Result<Self, Error>;
+}
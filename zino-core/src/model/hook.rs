@@ -76,7 +76,19 @@ pub trait ModelHooks: Model {
         };
         let execution_time = ctx.start_time().elapsed();
         let execution_time_millis = execution_time.as_millis();
-        if execution_time_millis > 3000 {
+        if ctx.is_slow_query() {
+            let model_name = ctx.model_name();
+            let request_id = ctx.request_id().map(|id| id.to_string());
+            tracing::warn!(
+                query_id,
+                request_id,
+                model_name,
+                query,
+                arguments,
+                execution_time_millis,
+                "slow query: {message}"
+            );
+        } else if execution_time_millis > 3000 {
             tracing::warn!(
                 query_id,
                 query,
@@ -140,6 +152,29 @@ pub trait ModelHooks: Model {
         Ok(())
     }
 
+    /// A hook running before deleting many models selected by the query from the table.
+    #[inline]
+    async fn before_delete_many(_query: &Query) -> Result<Self::Data, Error> {
+        Ok(Self::Data::default())
+    }
+
+    /// A hook running after deleting many models from the table.
+    #[inline]
+    async fn after_delete_many(ctx: &QueryContext, _data: Self::Data) -> Result<(), Error> {
+        if ctx.is_success() {
+            let query = ctx.query();
+            let query_id = ctx.query_id().to_string();
+            let rows_affected = ctx.rows_affected();
+            tracing::warn!(
+                query,
+                query_id,
+                rows_affected,
+                "models were deleted from the table"
+            );
+        }
+        Ok(())
+    }
+
     /// A hook running before logically deleting a model from the table.
     #[inline]
     async fn before_soft_delete(&mut self) -> Result<Self::Data, Error> {
@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+/// A composite index on multiple columns.
+#[derive(Debug, Clone, Serialize)]
+pub struct Index<'a> {
+    /// Optional index name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    /// Column names with an optional sort order, e.g. `created_at DESC`.
+    columns: Vec<&'a str>,
+    /// A flag which indicates whether the index is unique.
+    unique: bool,
+    /// Index type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_type: Option<&'a str>,
+    /// A `WHERE` predicate for the partial index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    predicate: Option<&'a str>,
+}
+
+impl<'a> Index<'a> {
+    /// Creates a new instance.
+    pub fn new(columns: Vec<&'a str>) -> Self {
+        Self {
+            name: None,
+            columns,
+            unique: false,
+            index_type: None,
+            predicate: None,
+        }
+    }
+
+    /// Sets the index name.
+    #[inline]
+    pub fn set_name(&mut self, name: &'a str) {
+        self.name = (!name.is_empty()).then_some(name);
+    }
+
+    /// Sets the `unique` flag.
+    #[inline]
+    pub fn set_unique(&mut self, unique: bool) {
+        self.unique = unique;
+    }
+
+    /// Sets the index type.
+    #[inline]
+    pub fn set_index_type(&mut self, index_type: &'a str) {
+        self.index_type = (!index_type.is_empty()).then_some(index_type);
+    }
+
+    /// Sets the `WHERE` predicate for a partial index.
+    /// Partial indexes are not supported by MySQL, in which case the index is skipped.
+    #[inline]
+    pub fn set_predicate(&mut self, predicate: &'a str) {
+        self.predicate = (!predicate.is_empty()).then_some(predicate);
+    }
+
+    /// Returns the index name.
+    #[inline]
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    /// Returns the column names.
+    #[inline]
+    pub fn columns(&self) -> &[&'a str] {
+        &self.columns
+    }
+
+    /// Returns `true` if the index is unique.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    /// Returns the index type.
+    #[inline]
+    pub fn index_type(&self) -> Option<&'a str> {
+        self.index_type
+    }
+
+    /// Returns the `WHERE` predicate.
+    #[inline]
+    pub fn predicate(&self) -> Option<&'a str> {
+        self.predicate
+    }
+
+    /// Returns the index name for the table.
+    /// If the name is not set, it is derived from the table name and the column names.
+    pub fn index_name(&self, table_name: &str) -> String {
+        if let Some(name) = self.name {
+            name.to_owned()
+        } else {
+            let columns = self
+                .columns
+                .iter()
+                .filter_map(|col| col.split_whitespace().next())
+                .collect::<Vec<_>>()
+                .join("_");
+            format!("{table_name}_{columns}_index")
+        }
+    }
+}
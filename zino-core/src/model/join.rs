@@ -0,0 +1,107 @@
+use crate::{JsonValue, Map};
+
+/// Formatter of the filters on the joined table, which pushes the bound values to the arguments.
+pub type JoinFiltersFormatter = fn(&Map, Option<&mut Vec<JsonValue>>) -> String;
+
+/// Types of the SQL join.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinType {
+    /// Inner join.
+    #[default]
+    Inner,
+    /// Left outer join.
+    Left,
+    /// Right outer join.
+    Right,
+}
+
+impl JoinType {
+    /// Returns the SQL keyword of the join type.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inner => "INNER JOIN",
+            Self::Left => "LEFT OUTER JOIN",
+            Self::Right => "RIGHT OUTER JOIN",
+        }
+    }
+}
+
+/// A join clause in the model query.
+#[derive(Debug, Clone)]
+pub struct Join {
+    /// Join type.
+    join_type: JoinType,
+    /// Table name aliased by the model name.
+    table_name: String,
+    /// Conditions of the `ON` clause.
+    conditions: String,
+    /// Projection fields of the joined table.
+    projection: String,
+    /// Filters on the joined table and the formatter of them.
+    filters: Option<(Map, JoinFiltersFormatter)>,
+}
+
+impl Join {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(
+        join_type: JoinType,
+        table_name: impl Into<String>,
+        conditions: impl Into<String>,
+        projection: impl Into<String>,
+    ) -> Self {
+        Self {
+            join_type,
+            table_name: table_name.into(),
+            conditions: conditions.into(),
+            projection: projection.into(),
+            filters: None,
+        }
+    }
+
+    /// Sets the filters on the joined table, which are appended to the `ON` clause
+    /// with the values bound as arguments.
+    #[inline]
+    pub fn set_filters(&mut self, filters: Map, formatter: JoinFiltersFormatter) {
+        self.filters = (!filters.is_empty()).then_some((filters, formatter));
+    }
+
+    /// Returns the join type.
+    #[inline]
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    /// Returns the table name.
+    #[inline]
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Returns the conditions of the `ON` clause.
+    #[inline]
+    pub fn conditions(&self) -> &str {
+        &self.conditions
+    }
+
+    /// Returns the projection fields of the joined table.
+    #[inline]
+    pub fn projection(&self) -> &str {
+        &self.projection
+    }
+
+    /// Formats the join clause and pushes the values of the filters to the arguments.
+    pub fn format_clause(&self, arguments: &mut Vec<JsonValue>) -> String {
+        let join_type = self.join_type.as_str();
+        let table_name = &self.table_name;
+        let conditions = &self.conditions;
+        if let Some((filters, formatter)) = &self.filters {
+            let filters = formatter(filters, Some(arguments));
+            if let Some(condition) = filters.strip_prefix("WHERE ") {
+                return format!("{join_type} {table_name} ON {conditions} AND {condition}");
+            }
+        }
+        format!("{join_type} {table_name} ON {conditions}")
+    }
+}
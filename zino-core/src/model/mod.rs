@@ -2,28 +2,41 @@
 use crate::{request::Validation, AvroValue, JsonValue, Map, Record};
 use serde::{de::DeserializeOwned, Serialize};
 
+mod aggregation;
 mod column;
 mod context;
+mod enum_column;
 mod hook;
+mod index;
+mod join;
 mod mutation;
 mod query;
 mod reference;
 mod row;
 mod translation;
+mod typed_column;
 
 #[doc(no_inline)]
 pub use apache_avro::schema;
 
+pub use aggregation::{AggregateFunction, Aggregation, DateBucket};
 pub use column::{Column, EncodeColumn};
 pub use context::QueryContext;
+pub use enum_column::EnumColumn;
 pub use hook::ModelHooks;
+pub use index::Index;
+pub use join::{Join, JoinFiltersFormatter, JoinType};
 pub use mutation::{Mutation, MutationBuilder};
 pub use query::{Query, QueryBuilder};
 pub use reference::Reference;
 pub use row::DecodeRow;
 pub use translation::Translation;
+pub use typed_column::TypedColumn;
 
 /// General data model.
+///
+/// It can be derived with `#[derive(Model)]` from `zino-derive`,
+/// which generates the `read_map` method from the field types.
 pub trait Model: Default + Serialize + DeserializeOwned {
     /// Creates a new instance.
     fn new() -> Self;
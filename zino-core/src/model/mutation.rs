@@ -1,8 +1,10 @@
+use super::TypedColumn;
 use crate::{
     extension::{JsonObjectExt, JsonValueExt},
     request::Validation,
     JsonValue, Map,
 };
+use serde::Serialize;
 
 #[derive(Debug, Clone, Default)]
 /// A mutation type for models.
@@ -145,6 +147,31 @@ impl MutationBuilder {
         self
     }
 
+    /// Sets the value of a typed column.
+    #[inline]
+    pub fn set_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.updates.upsert(column.name(), value);
+        self
+    }
+
+    /// Increments a typed column by a specified value.
+    #[inline]
+    pub fn inc_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.updates
+            .upsert(column.name(), Map::from_entry("$inc", value));
+        self
+    }
+
     /// Constructs an instance of `Mutation`.
     #[inline]
     pub fn build(self) -> Mutation {
@@ -1,8 +1,12 @@
+use super::{Join, TypedColumn};
 use crate::{
+    encoding::base64,
+    error::Error,
     extension::{JsonObjectExt, JsonValueExt},
     request::Validation,
     JsonValue, Map, SharedString,
 };
+use serde::Serialize;
 
 #[derive(Debug, Clone)]
 /// A query type for models.
@@ -17,6 +21,12 @@ pub struct Query {
     offset: usize,
     // Limit.
     limit: usize,
+    // Cursor for the keyset pagination.
+    cursor: Option<Map>,
+    // Join clauses.
+    joins: Vec<Join>,
+    // Relations for the eager loading.
+    includes: Vec<String>,
 }
 
 impl Query {
@@ -29,6 +39,9 @@ impl Query {
             sort_order: Vec::new(),
             offset: 0,
             limit: 0,
+            cursor: None,
+            joins: Vec::new(),
+            includes: Vec::new(),
         }
     }
 
@@ -45,6 +58,11 @@ impl Query {
                         self.fields = fields.into_iter().map(|s| s.to_owned()).collect();
                     }
                 }
+                "include" | "includes" => {
+                    if let Some(includes) = value.parse_str_array() {
+                        self.includes = includes.into_iter().map(|s| s.to_owned()).collect();
+                    }
+                }
                 "order_by" | "sort_by" => {
                     if let Some(sort_order) = value.parse_str_array() {
                         self.sort_order = sort_order
@@ -77,6 +95,17 @@ impl Query {
                         }
                     }
                 }
+                "cursor" => {
+                    if let Some(cursor) = value.as_str() {
+                        match base64::decode_url_safe(cursor) {
+                            Ok(bytes) => match serde_json::from_slice::<Map>(&bytes) {
+                                Ok(cursor) => self.cursor = Some(cursor),
+                                Err(err) => validation.record_fail("cursor", err),
+                            },
+                            Err(err) => validation.record_fail("cursor", err),
+                        }
+                    }
+                }
                 "current_page" => {
                     if let Some(result) = value.parse_usize() {
                         match result {
@@ -85,7 +114,8 @@ impl Query {
                         }
                     }
                 }
-                "populate" | "translate" | "show_deleted" | "validate_only" => {
+                "populate" | "translate" | "show_deleted" | "validate_only"
+                | "read_your_writes" => {
                     if let Some(result) = value.parse_bool() {
                         match result {
                             Ok(flag) => {
@@ -160,6 +190,24 @@ impl Query {
             .retain(|field| !fields.contains(&field.as_str()))
     }
 
+    /// Adds the sort fields to the projection if they are not selected,
+    /// which is required by [`Query::next_cursor()`].
+    pub fn add_sort_fields(&mut self) {
+        if self.fields.is_empty() {
+            return;
+        }
+        for (field, _) in self.sort_order.iter() {
+            let field = field.as_ref();
+            let selected = self.fields.iter().any(|s| {
+                let (alias, _) = s.split_once(':').unwrap_or((s, ""));
+                alias.trim() == field
+            });
+            if !selected {
+                self.fields.push(field.to_owned());
+            }
+        }
+    }
+
     /// Adds a key-value pair to the query filters.
     #[inline]
     pub fn add_filter(&mut self, key: impl Into<String>, value: impl Into<JsonValue>) {
@@ -172,6 +220,12 @@ impl Query {
         self.filters.append(filters);
     }
 
+    /// Sets the relations to be eager loaded.
+    #[inline]
+    pub fn set_includes(&mut self, relations: &[&str]) {
+        self.includes = relations.iter().map(|&s| s.to_owned()).collect();
+    }
+
     /// Sets the sort order.
     #[inline]
     pub fn set_sort_order(&mut self, field: impl Into<SharedString>, descending: bool) {
@@ -192,6 +246,38 @@ impl Query {
         self.limit = limit;
     }
 
+    /// Sets the cursor for the keyset pagination.
+    /// The cursor should be the one returned by [`Query::next_cursor()`].
+    pub fn set_cursor(&mut self, cursor: &str) -> Result<(), Error> {
+        let bytes = base64::decode_url_safe(cursor)?;
+        let cursor = serde_json::from_slice::<Map>(&bytes)?;
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Returns an opaque cursor which encodes the sort keys of the last row,
+    /// or `None` if there are no more rows after the current page.
+    /// Null sort keys are encoded as they are, and `None` is also returned
+    /// if a sort key is missing in the last row.
+    pub fn next_cursor(&self, rows: &[Map]) -> Option<String> {
+        if self.sort_order.is_empty() || rows.len() < self.limit {
+            return None;
+        }
+
+        let last_row = rows.last()?;
+        let mut cursor = Map::new();
+        for (field, _) in self.sort_order.iter() {
+            let field = field.as_ref();
+            let Some(value) = last_row.get(field) else {
+                tracing::warn!("the sort key `{field}` is missing for the cursor");
+                return None;
+            };
+            cursor.upsert(field, value.clone());
+        }
+        let bytes = serde_json::to_vec(&cursor).ok()?;
+        Some(base64::encode_url_safe(bytes))
+    }
+
     /// Returns a reference to the projection fields.
     #[inline]
     pub fn fields(&self) -> &[String] {
@@ -222,6 +308,30 @@ impl Query {
         self.limit
     }
 
+    /// Returns a reference to the decoded cursor for the keyset pagination.
+    #[inline]
+    pub fn cursor(&self) -> Option<&Map> {
+        self.cursor.as_ref()
+    }
+
+    /// Returns a reference to the join clauses.
+    #[inline]
+    pub fn joins(&self) -> &[Join] {
+        &self.joins
+    }
+
+    /// Returns the relations to be eager loaded.
+    #[inline]
+    pub fn includes(&self) -> &[String] {
+        self.includes.as_slice()
+    }
+
+    /// Returns `true` if the relation should be eager loaded.
+    #[inline]
+    pub fn is_included(&self, relation: &str) -> bool {
+        self.includes.iter().any(|s| s == relation)
+    }
+
     /// Returns `true` if the `flag` has been enabled.
     #[inline]
     pub fn enabled(&self, flag: &str) -> bool {
@@ -246,6 +356,12 @@ impl Query {
         self.enabled("show_deleted")
     }
 
+    /// Returns `true` if the `read_your_writes` flag has been enabled.
+    #[inline]
+    pub fn read_your_writes(&self) -> bool {
+        self.enabled("read_your_writes")
+    }
+
     /// Returns `true` if the `validate_only` flag has been enabled.
     #[inline]
     pub fn validate_only(&self) -> bool {
@@ -262,6 +378,9 @@ impl Default for Query {
             sort_order: Vec::new(),
             offset: 0,
             limit: 10,
+            cursor: None,
+            joins: Vec::new(),
+            includes: Vec::new(),
         }
     }
 }
@@ -279,6 +398,12 @@ pub struct QueryBuilder {
     offset: usize,
     // Limit.
     limit: usize,
+    // Cursor for the keyset pagination.
+    cursor: Option<Map>,
+    // Join clauses.
+    joins: Vec<Join>,
+    // Relations for the eager loading.
+    includes: Vec<String>,
 }
 
 impl QueryBuilder {
@@ -291,6 +416,9 @@ impl QueryBuilder {
             sort_order: Vec::new(),
             offset: 0,
             limit: usize::MAX,
+            cursor: None,
+            joins: Vec::new(),
+            includes: Vec::new(),
         }
     }
 
@@ -422,6 +550,111 @@ impl QueryBuilder {
         self
     }
 
+    /// Adds a filter with the condition for equal parts of a typed column.
+    #[inline]
+    pub fn and_eq_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters.upsert(column.name(), value);
+        self
+    }
+
+    /// Adds a filter with the condition for non-equal parts of a typed column.
+    #[inline]
+    pub fn and_ne_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters
+            .upsert(column.name(), Map::from_entry("$ne", value));
+        self
+    }
+
+    /// Adds a filter with the condition for a column less than the value of a typed column.
+    #[inline]
+    pub fn and_lt_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters
+            .upsert(column.name(), Map::from_entry("$lt", value));
+        self
+    }
+
+    /// Adds a filter with the condition for a column not greater than the value of a typed column.
+    #[inline]
+    pub fn and_le_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters
+            .upsert(column.name(), Map::from_entry("$le", value));
+        self
+    }
+
+    /// Adds a filter with the condition for a column greater than the value of a typed column.
+    #[inline]
+    pub fn and_gt_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters
+            .upsert(column.name(), Map::from_entry("$gt", value));
+        self
+    }
+
+    /// Adds a filter with the condition for a column not less than the value of a typed column.
+    #[inline]
+    pub fn and_ge_col<T, V>(mut self, column: TypedColumn<T>, value: V) -> Self
+    where
+        T: Serialize,
+        V: Into<T>,
+    {
+        let value = column.encode_value(value.into());
+        self.filters
+            .upsert(column.name(), Map::from_entry("$ge", value));
+        self
+    }
+
+    /// Adds a filter with the condition for a typed column whose value is in the list.
+    pub fn and_in_col<T>(mut self, column: TypedColumn<T>, list: &[T]) -> Self
+    where
+        T: Serialize + Clone,
+    {
+        let values = list
+            .iter()
+            .map(|value| column.encode_value(value.clone()))
+            .collect::<Vec<_>>();
+        self.filters
+            .upsert(column.name(), Map::from_entry("$in", values));
+        self
+    }
+
+    /// Adds a filter with the condition for a typed column whose value is not in the list.
+    pub fn and_not_in_col<T>(mut self, column: TypedColumn<T>, list: &[T]) -> Self
+    where
+        T: Serialize + Clone,
+    {
+        let values = list
+            .iter()
+            .map(|value| column.encode_value(value.clone()))
+            .collect::<Vec<_>>();
+        self.filters
+            .upsert(column.name(), Map::from_entry("$nin", values));
+        self
+    }
+
     /// Adds a filter which groups rows that have the same values into summary rows.
     #[inline]
     pub fn group_by<T: Into<JsonValue>>(mut self, fields: T) -> Self {
@@ -471,6 +704,31 @@ impl QueryBuilder {
         self
     }
 
+    /// Sets the cursor for the keyset pagination.
+    /// An invalid cursor is ignored.
+    pub fn cursor(mut self, cursor: &str) -> Self {
+        let mut query = Query::default();
+        if let Err(err) = query.set_cursor(cursor) {
+            tracing::warn!("fail to decode the cursor `{cursor}`: {err}");
+        }
+        self.cursor = query.cursor;
+        self
+    }
+
+    /// Adds a join clause.
+    #[inline]
+    pub fn join(mut self, join: Join) -> Self {
+        self.joins.push(join);
+        self
+    }
+
+    /// Adds a relation to be eager loaded.
+    #[inline]
+    pub fn include<S: Into<String>>(mut self, relation: S) -> Self {
+        self.includes.push(relation.into());
+        self
+    }
+
     /// Constructs an instance of `Query`.
     #[inline]
     pub fn build(self) -> Query {
@@ -480,6 +738,50 @@ impl QueryBuilder {
             sort_order: self.sort_order,
             offset: self.offset,
             limit: self.limit,
+            cursor: self.cursor,
+            joins: self.joins,
+            includes: self.includes,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use crate::{extension::JsonObjectExt, JsonValue, Map};
+
+    #[test]
+    fn it_encodes_null_values_in_cursor() {
+        let mut query = Query::default();
+        query.set_sort_order("name", false);
+        query.set_sort_order("id", true);
+        query.set_limit(1);
+
+        let mut row = Map::new();
+        row.upsert("name", JsonValue::Null);
+        row.upsert("id", 1);
+        let cursor = query.next_cursor(&[row]).unwrap();
+        query.set_cursor(&cursor).unwrap();
+
+        let cursor = query.cursor().unwrap();
+        assert_eq!(cursor.get("name"), Some(&JsonValue::Null));
+        assert_eq!(cursor.get_i64("id"), Some(1));
+
+        let mut row = Map::new();
+        row.upsert("id", 2);
+        assert_eq!(query.next_cursor(&[row]), None);
+    }
+
+    #[test]
+    fn it_adds_sort_fields() {
+        let mut query = Query::default();
+        query.allow_fields(&["id", "name", "label: upper(name)"]);
+        query.set_sort_order("label", false);
+        query.set_sort_order("updated_at", true);
+        query.add_sort_fields();
+        assert_eq!(
+            query.fields(),
+            ["id", "name", "label: upper(name)", "updated_at"]
+        );
+    }
+}
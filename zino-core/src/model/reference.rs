@@ -7,13 +7,48 @@ pub struct Reference<'a> {
     name: &'a str,
     /// Column name.
     column_name: &'a str,
+    /// A flag which indicates whether a foreign key constraint should be generated.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    foreign_key: bool,
+    /// The `ON DELETE` action of the foreign key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_delete: Option<&'a str>,
+    /// The `ON UPDATE` action of the foreign key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_update: Option<&'a str>,
 }
 
 impl<'a> Reference<'a> {
     /// Creates a new instance.
     #[inline]
     pub fn new(name: &'a str, column_name: &'a str) -> Self {
-        Self { name, column_name }
+        Self {
+            name,
+            column_name,
+            foreign_key: false,
+            on_delete: None,
+            on_update: None,
+        }
+    }
+
+    /// Sets the `foreign_key` flag.
+    #[inline]
+    pub fn set_foreign_key(&mut self, foreign_key: bool) {
+        self.foreign_key = foreign_key;
+    }
+
+    /// Sets the `ON DELETE` action and enables the foreign key constraint.
+    #[inline]
+    pub fn set_on_delete(&mut self, action: &'a str) {
+        self.foreign_key = true;
+        self.on_delete = (!action.is_empty()).then_some(action);
+    }
+
+    /// Sets the `ON UPDATE` action and enables the foreign key constraint.
+    #[inline]
+    pub fn set_on_update(&mut self, action: &'a str) {
+        self.foreign_key = true;
+        self.on_update = (!action.is_empty()).then_some(action);
     }
 
     /// Returns the reference name.
@@ -27,4 +62,22 @@ impl<'a> Reference<'a> {
     pub fn column_name(&self) -> &'a str {
         self.column_name
     }
+
+    /// Returns `true` if a foreign key constraint should be generated.
+    #[inline]
+    pub fn is_foreign_key(&self) -> bool {
+        self.foreign_key
+    }
+
+    /// Returns the `ON DELETE` action.
+    #[inline]
+    pub fn on_delete(&self) -> Option<&'a str> {
+        self.on_delete
+    }
+
+    /// Returns the `ON UPDATE` action.
+    #[inline]
+    pub fn on_update(&self) -> Option<&'a str> {
+        self.on_update
+    }
 }
@@ -0,0 +1,87 @@
+use crate::{JsonValue, SharedString};
+use serde::Serialize;
+use std::{fmt, marker::PhantomData};
+
+/// A column name which carries the Rust type of the field.
+///
+/// The typed columns of a model are generated by `#[derive(Schema)]`
+/// as a constant namespace, such as `User::COL.status`.
+pub struct TypedColumn<T> {
+    /// Column name.
+    name: &'static str,
+    /// Phantom data of the field type.
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedColumn<T> {
+    /// Creates a new instance.
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the column name.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: Serialize> TypedColumn<T> {
+    /// Encodes the value of the column as a JSON value.
+    /// If the value can not be serialized as JSON, such as a map with non-string keys,
+    /// the error is logged and `null` is returned.
+    pub fn encode_value(&self, value: T) -> JsonValue {
+        serde_json::to_value(value).unwrap_or_else(|err| {
+            let name = self.name;
+            tracing::error!(name, "fail to encode the value of the column: {err}");
+            JsonValue::Null
+        })
+    }
+}
+
+impl<T> Clone for TypedColumn<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedColumn<T> {}
+
+impl<T> fmt::Debug for TypedColumn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedColumn").field(&self.name).finish()
+    }
+}
+
+impl<T> fmt::Display for TypedColumn<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl<T> AsRef<str> for TypedColumn<T> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl<T> From<TypedColumn<T>> for String {
+    #[inline]
+    fn from(column: TypedColumn<T>) -> Self {
+        column.name.to_owned()
+    }
+}
+
+impl<T> From<TypedColumn<T>> for SharedString {
+    #[inline]
+    fn from(column: TypedColumn<T>) -> Self {
+        SharedString::Borrowed(column.name)
+    }
+}
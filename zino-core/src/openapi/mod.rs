@@ -29,7 +29,7 @@ mod model;
 mod parser;
 mod webhook;
 
-pub(crate) use model::translate_model_entry;
+pub(crate) use model::{register_model_schema, translate_model_entry};
 pub(crate) use webhook::get_webhook;
 
 /// Constructs the OpenAPI `Info` object.
@@ -202,6 +202,11 @@ pub(crate) fn default_components() -> Components {
         .responses
         .insert("4XX".to_owned(), error_response.into());
 
+    // Model schemas
+    for (schema_name, schema) in model::model_schemas() {
+        components.schemas.insert(schema_name, schema.into());
+    }
+
     components
 }
 
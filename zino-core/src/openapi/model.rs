@@ -1,6 +1,28 @@
-use crate::{extension::JsonObjectExt, model::Translation, Map};
+use super::parser;
+use crate::{
+    extension::JsonObjectExt,
+    model::{Column, Translation},
+    Map,
+};
 use convert_case::{Case, Casing};
-use std::{collections::HashMap, sync::LazyLock};
+use parking_lot::RwLock;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::LazyLock,
+};
+use utoipa::openapi::schema::Schema;
+
+/// Registers the schema of a model into the OpenAPI components.
+pub(crate) fn register_model_schema(model_name: &str, columns: &[Column<'_>]) {
+    let schema_name = model_name.to_case(Case::Camel);
+    let schema = parser::parse_model_schema(columns);
+    MODEL_SCHEMAS.write().insert(schema_name, schema);
+}
+
+/// Returns the registered schemas of the models.
+pub(super) fn model_schemas() -> BTreeMap<String, Schema> {
+    MODEL_SCHEMAS.read().clone()
+}
 
 /// Translates the model data.
 pub(crate) fn translate_model_entry(model: &mut Map, model_name: &str) {
@@ -35,3 +57,7 @@ static MODEL_TRANSLATIONS: LazyLock<HashMap<&str, Translation>> = LazyLock::new(
     }
     model_translations
 });
+
+/// Schemas of the models.
+static MODEL_SCHEMAS: LazyLock<RwLock<BTreeMap<String, Schema>>> =
+    LazyLock::new(|| RwLock::new(BTreeMap::new()));
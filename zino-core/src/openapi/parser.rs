@@ -1,5 +1,6 @@
 use crate::{
     extension::{TomlTableExt, TomlValueExt},
+    model::Column,
     TomlValue,
 };
 use convert_case::{Case, Casing};
@@ -307,6 +308,78 @@ fn parse_array_schema(config: &Table) -> Array {
     array_builder.build()
 }
 
+/// Parses the schema of a model with the columns.
+pub(super) fn parse_model_schema(columns: &[Column<'_>]) -> Schema {
+    let mut object_builder = ObjectBuilder::new().schema_type(SchemaType::Object);
+    for col in columns {
+        let name = col.name();
+        object_builder = object_builder.property(name, parse_column_schema(col));
+        if col.is_required() {
+            object_builder = object_builder.required(name);
+        }
+    }
+    Schema::Object(object_builder.build())
+}
+
+/// Parses the schema of a column with the validation rules.
+fn parse_column_schema(col: &Column<'_>) -> Schema {
+    let type_name = col.type_name();
+    if let Some(item_type) = type_name
+        .strip_prefix("Vec<")
+        .and_then(|s| s.strip_suffix('>'))
+    {
+        let item_schema = Schema::Object(parse_column_type(item_type).build());
+        let array = ArrayBuilder::new().items(item_schema).build();
+        return Schema::Array(array);
+    }
+
+    let mut object_builder = parse_column_type(type_name);
+    if let Some(format) = col.format() {
+        object_builder = object_builder.format(Some(parse_schema_format(format)));
+    }
+    if let Some(pattern) = col.pattern() {
+        object_builder = object_builder.pattern(Some(pattern));
+    }
+    if let Some(minimum) = col.minimum() {
+        object_builder = object_builder.minimum(Some(minimum));
+    }
+    if let Some(maximum) = col.maximum() {
+        object_builder = object_builder.maximum(Some(maximum));
+    }
+    let enum_values = col.enum_values();
+    if !enum_values.is_empty() {
+        object_builder = object_builder.enum_values(Some(enum_values.iter().copied()));
+    }
+    Schema::Object(object_builder.build())
+}
+
+/// Parses the type of a column as an object builder.
+fn parse_column_type(type_name: &str) -> ObjectBuilder {
+    let (type_name, nullable) = match type_name
+        .strip_prefix("Option<")
+        .and_then(|s| s.strip_suffix('>'))
+    {
+        Some(type_name) => (type_name, true),
+        None => (type_name, false),
+    };
+    let (schema_type, format) = match type_name {
+        "bool" => (SchemaType::Boolean, None),
+        "i8" | "i16" | "i32" | "u8" | "u16" | "u32" => (SchemaType::Integer, Some("int32")),
+        "i64" | "u64" | "isize" | "usize" => (SchemaType::Integer, Some("int64")),
+        "f32" => (SchemaType::Number, Some("float")),
+        "f64" => (SchemaType::Number, Some("double")),
+        "String" => (SchemaType::String, None),
+        "DateTime" => (SchemaType::String, Some("datetime")),
+        "Uuid" => (SchemaType::String, Some("uuid")),
+        "Map" => (SchemaType::Object, None),
+        _ => (SchemaType::Value, None),
+    };
+    ObjectBuilder::new()
+        .schema_type(schema_type)
+        .format(format.map(parse_schema_format))
+        .nullable(nullable)
+}
+
 /// Parses the path item type.
 pub(super) fn parse_path_item_type(method: &str) -> PathItemType {
     match method {
@@ -351,7 +424,7 @@ fn parse_schema_format(format: &str) -> SchemaFormat {
         "byte" => SchemaFormat::KnownFormat(KnownFormat::Byte),
         "binary" => SchemaFormat::KnownFormat(KnownFormat::Binary),
         "date" => SchemaFormat::KnownFormat(KnownFormat::Date),
-        "datetime" => SchemaFormat::KnownFormat(KnownFormat::DateTime),
+        "datetime" | "date-time" => SchemaFormat::KnownFormat(KnownFormat::DateTime),
         "password" => SchemaFormat::KnownFormat(KnownFormat::Password),
         "uuid" => SchemaFormat::KnownFormat(KnownFormat::Uuid),
         _ => SchemaFormat::Custom(format.to_owned()),
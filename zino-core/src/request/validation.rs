@@ -1,4 +1,13 @@
-use crate::{error::Error, extension::JsonObjectExt, Map, SharedString};
+use crate::{error::Error, extension::JsonObjectExt, Map, SharedString, Uuid};
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use parking_lot::RwLock;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::LazyLock,
+};
+use url::Url;
 
 /// A record of validation results.
 #[derive(Debug, Default)]
@@ -56,4 +65,92 @@ impl Validation {
         }
         map
     }
+
+    /// Validates the value with the format.
+    /// Supported formats: `email`, `uri`, `uuid`, `date`, `time`, `date-time`, `ipv4` and `ipv6`.
+    /// Unknown formats are ignored.
+    pub fn validate_format(format: &str, value: &str) -> Result<(), Error> {
+        let valid = match format {
+            "email" => is_email(value),
+            "uri" | "url" => Url::parse(value).is_ok(),
+            "uuid" => value.parse::<Uuid>().is_ok(),
+            "date" => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+            "time" => NaiveTime::parse_from_str(value, "%H:%M:%S%.f").is_ok(),
+            "date-time" => DateTime::parse_from_rfc3339(value).is_ok(),
+            "ipv4" => value.parse::<Ipv4Addr>().is_ok(),
+            "ipv6" => value.parse::<Ipv6Addr>().is_ok(),
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::new(format!("it should be a valid `{format}` value")))
+        }
+    }
+
+    /// Validates the value with the regex pattern. The compiled regex is cached.
+    pub fn validate_pattern(pattern: &'static str, value: &str) -> Result<(), Error> {
+        let cached_match = REGEX_PATTERNS
+            .read()
+            .get(pattern)
+            .map(|regex| regex.is_match(value));
+        let is_match = match cached_match {
+            Some(is_match) => is_match,
+            None => {
+                let regex = Regex::new(pattern)?;
+                let is_match = regex.is_match(value);
+                REGEX_PATTERNS.write().insert(pattern, regex);
+                is_match
+            }
+        };
+        if is_match {
+            Ok(())
+        } else {
+            Err(Error::new(format!(
+                "it should match the pattern `{pattern}`"
+            )))
+        }
+    }
+}
+
+/// Returns `true` if the value is a valid email address.
+fn is_email(value: &str) -> bool {
+    if let Some((local_part, domain)) = value.rsplit_once('@') {
+        !local_part.is_empty()
+            && local_part.len() <= 64
+            && !local_part.contains(|c: char| c.is_whitespace() || c == '@')
+            && domain.split('.').count() > 1
+            && domain.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+            })
+    } else {
+        false
+    }
+}
+
+/// Compiled regex patterns.
+static REGEX_PATTERNS: LazyLock<RwLock<HashMap<&'static str, Regex>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[cfg(test)]
+mod tests {
+    use super::Validation;
+
+    #[test]
+    fn it_validates_formats() {
+        assert!(Validation::validate_format("email", "alice@example.com").is_ok());
+        assert!(Validation::validate_format("email", "alice@localhost").is_err());
+        assert!(Validation::validate_format("email", "alice example@example.com").is_err());
+        assert!(Validation::validate_format("uri", "https://example.com/path").is_ok());
+        assert!(Validation::validate_format("uri", "example.com").is_err());
+        assert!(Validation::validate_format("date", "2023-09-30").is_ok());
+        assert!(Validation::validate_format("date-time", "2023-09-30T08:00:00Z").is_ok());
+        assert!(Validation::validate_format("ipv4", "127.0.0.1").is_ok());
+        assert!(Validation::validate_format("ipv6", "127.0.0.1").is_err());
+        assert!(Validation::validate_pattern(r"^\d{3}-\d{4}$", "555-0100").is_ok());
+        assert!(Validation::validate_pattern(r"^\d{3}-\d{4}$", "5550100").is_err());
+    }
 }
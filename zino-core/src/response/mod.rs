@@ -2,21 +2,28 @@
 
 use crate::{
     error::Error,
-    extension::JsonValueExt,
+    extension::{JsonObjectExt, JsonValueExt},
     file::NamedFile,
     helper,
     request::{RequestContext, Validation},
     trace::{ServerTiming, TimingMetric, TraceContext},
-    JsonValue, SharedString, Uuid,
+    JsonValue, Map, SharedString, Uuid,
 };
 use bytes::Bytes;
 use etag::EntityTag;
+use futures::{
+    future,
+    stream::{BoxStream, Stream, StreamExt},
+};
 use http::header::{self, HeaderName, HeaderValue};
 use http_body::Full;
+use parking_lot::Mutex;
 use serde::Serialize;
 use serde_json::value::RawValue;
 use std::{
+    fmt,
     marker::PhantomData,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -37,6 +44,12 @@ pub type FullResponse = http::Response<Full<Bytes>>;
 /// A function pointer of transforming the response data.
 pub type DataTransformer = fn(data: &JsonValue) -> Result<Bytes, Error>;
 
+/// A stream of bytes chunks for the response body.
+pub type BytesStream = BoxStream<'static, Result<Bytes, Error>>;
+
+/// Number of records encoded in a chunk of the streaming response body.
+const STREAM_CHUNK_SIZE: usize = 256;
+
 /// An HTTP response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -82,6 +95,9 @@ pub struct Response<S = StatusCode> {
     /// Bytes data.
     #[serde(skip)]
     bytes_data: Bytes,
+    /// Stream data.
+    #[serde(skip)]
+    stream_data: StreamData,
     /// Transformer of the response data.
     #[serde(skip)]
     data_transformer: Option<DataTransformer>,
@@ -121,6 +137,7 @@ impl<S: ResponseCode> Response<S> {
             data: None,
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
+            stream_data: StreamData::default(),
             data_transformer: None,
             content_type: None,
             trace_context: None,
@@ -154,6 +171,7 @@ impl<S: ResponseCode> Response<S> {
             data: None,
             json_data: JsonValue::Null,
             bytes_data: Bytes::new(),
+            stream_data: StreamData::default(),
             data_transformer: None,
             content_type: None,
             trace_context: None,
@@ -297,6 +315,18 @@ impl<S: ResponseCode> Response<S> {
         self.bytes_data = bytes.into();
     }
 
+    /// Sets the stream data which is sent as a chunked response body.
+    #[inline]
+    pub fn set_stream_data<St>(&mut self, stream: St)
+    where
+        St: Stream<Item = Result<Bytes, Error>> + Send + 'static,
+    {
+        self.data = None;
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.stream_data = StreamData(Some(Arc::new(Mutex::new(Some(stream.boxed())))));
+    }
+
     /// Sets the response data for the validation.
     #[inline]
     pub fn set_validation_data(&mut self, validation: Validation) {
@@ -379,6 +409,32 @@ impl<S: ResponseCode> Response<S> {
         self.set_data_transformer(|data| Ok(data.to_csv(Vec::new())?.into()));
     }
 
+    /// Sets the response body as a stream of the CSV records.
+    pub fn set_csv_stream_response<St>(&mut self, stream: St)
+    where
+        St: Stream<Item = Result<Map, Error>> + Send + 'static,
+    {
+        let stream = stream
+            .ready_chunks(STREAM_CHUNK_SIZE)
+            .scan(Vec::new(), |headers, records| {
+                future::ready(Some(encode_csv_records(headers, records)))
+            });
+        self.set_stream_data(stream);
+        self.set_content_type("text/csv; charset=utf-8");
+    }
+
+    /// Sets the response body as a stream of the JSON Lines records.
+    pub fn set_jsonlines_stream_response<St>(&mut self, stream: St)
+    where
+        St: Stream<Item = Result<Map, Error>> + Send + 'static,
+    {
+        let stream = stream
+            .ready_chunks(STREAM_CHUNK_SIZE)
+            .map(encode_jsonlines_records);
+        self.set_stream_data(stream);
+        self.set_content_type("application/jsonlines; charset=utf-8");
+    }
+
     /// Sets the request ID.
     #[inline]
     pub(crate) fn set_request_id(&mut self, request_id: Uuid) {
@@ -506,6 +562,12 @@ impl<S: ResponseCode> Response<S> {
         self.server_timing.to_string()
     }
 
+    /// Takes the stream data out of the response, leaving `None` in its place.
+    #[inline]
+    pub fn take_stream_data(&mut self) -> Option<BytesStream> {
+        self.stream_data.0.as_ref().and_then(|stream| stream.lock().take())
+    }
+
     /// Reads the response into a byte buffer.
     pub fn read_bytes(&mut self) -> Result<Bytes, Error> {
         let bytes_opt = if !self.bytes_data.is_empty() {
@@ -632,6 +694,50 @@ impl<S: ResponseCode> Response<S> {
     }
 }
 
+/// A stream of the response body which can only be taken once.
+#[derive(Clone, Default)]
+struct StreamData(Option<Arc<Mutex<Option<BytesStream>>>>);
+
+impl fmt::Debug for StreamData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StreamData")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// Encodes the records as CSV bytes. The headers are written before the first record.
+fn encode_csv_records(
+    headers: &mut Vec<String>,
+    records: Vec<Result<Map, Error>>,
+) -> Result<Bytes, Error> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        let record = record?;
+        if headers.is_empty() {
+            headers.extend(record.keys().cloned());
+            wtr.write_record(headers.iter())?;
+        }
+
+        let fields = headers
+            .iter()
+            .map(|field| record.parse_string(field).unwrap_or_default());
+        wtr.write_record(fields.map(|field| field.into_owned()))?;
+    }
+    let bytes = wtr.into_inner().map_err(|err| err.into_error())?;
+    Ok(bytes.into())
+}
+
+/// Encodes the records as JSON Lines bytes.
+fn encode_jsonlines_records(records: Vec<Result<Map, Error>>) -> Result<Bytes, Error> {
+    let mut bytes = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut bytes, &record?)?;
+        bytes.push(b'\n');
+    }
+    Ok(bytes.into())
+}
+
 impl<S: ResponseCode> Default for Response<S> {
     #[inline]
     fn default() -> Self {
@@ -128,9 +128,20 @@ impl Rejection {
         Self::bad_request(validation)
     }
 
-    /// Creates a new instance from an error classified by the error message.
+    /// Creates a new instance from an error classified by the status code
+    /// or the error message.
     pub fn from_error(err: impl Into<Error>) -> Self {
         let err = err.into();
+        match err.status_code() {
+            Some(401) => return Self::unauthorized(err),
+            Some(403) => return Self::forbidden(err),
+            Some(404) => return Self::not_found(err),
+            Some(405) => return Self::method_not_allowed(err),
+            Some(409) => return Self::conflict(err),
+            Some(503) => return Self::service_unavailable(err),
+            _ => (),
+        }
+
         let message = err.message();
         if message.starts_with("401 Unauthorized") {
             Self::unauthorized(err)
@@ -27,6 +27,11 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields};
 mod parser;
 
 /// Derive the `Schema` trait.
+///
+/// A field with the `reference` attribute generates a foreign key constraint
+/// if `foreign_key`, `on_delete` or `on_update` is specified.
+/// The constraint is only created along with the table,
+/// and it is not added to an existing table by `Schema::migrate`.
 #[proc_macro_derive(Schema, attributes(schema))]
 pub fn schema_macro(item: TokenStream) -> TokenStream {
     /// Integer types
@@ -45,8 +50,72 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
     let mut reader_name = String::from("main");
     let mut writer_name = String::from("main");
     let mut table_name = None;
+    let mut tenant_id_field = None;
+    let mut cache_ttl = None;
     let mut documentation = None;
+    let mut indexes = Vec::new();
     for attr in input.attrs.iter() {
+        for arguments in parser::parse_schema_list_attr(attr, "index").into_iter() {
+            let mut index_name = None;
+            let mut index_columns = Vec::new();
+            let mut unique = false;
+            let mut index_type = None;
+            let mut predicate = None;
+            for (key, value) in arguments.into_iter() {
+                match key.as_str() {
+                    "name" => {
+                        index_name = value;
+                    }
+                    "columns" => {
+                        if let Some(value) = value {
+                            index_columns = value
+                                .split(',')
+                                .map(|col| col.trim().to_owned())
+                                .filter(|col| !col.is_empty())
+                                .collect();
+                        }
+                    }
+                    "unique" => {
+                        unique = value.map(|v| v == "true").unwrap_or(true);
+                    }
+                    "index_type" => {
+                        index_type = value;
+                    }
+                    "predicate" => {
+                        predicate = value;
+                    }
+                    _ => (),
+                }
+            }
+            if index_columns.is_empty() {
+                continue;
+            }
+
+            let quote_name = if let Some(name) = index_name {
+                quote! { index.set_name(#name); }
+            } else {
+                quote! {}
+            };
+            let quote_index_type = if let Some(index_type) = index_type {
+                quote! { index.set_index_type(#index_type); }
+            } else {
+                quote! {}
+            };
+            let quote_predicate = if let Some(predicate) = predicate {
+                quote! { index.set_predicate(#predicate); }
+            } else {
+                quote! {}
+            };
+            let index = quote! {{
+                let mut index = zino_core::model::Index::new(vec![#(#index_columns),*]);
+                index.set_unique(#unique);
+                #quote_name
+                #quote_index_type
+                #quote_predicate
+                index
+            }};
+            indexes.push(index);
+        }
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if let Some(value) = value {
                 match key.as_str() {
@@ -62,6 +131,14 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                     "table_name" => {
                         table_name = Some(value);
                     }
+                    "tenant_id_field" => {
+                        tenant_id_field = Some(value);
+                    }
+                    "cache_ttl" => {
+                        if let Ok(secs) = value.parse::<u64>() {
+                            cache_ttl = Some(secs);
+                        }
+                    }
                     "doc" => {
                         documentation = Some(value);
                     }
@@ -78,6 +155,10 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
     let mut column_fields = Vec::new();
     let mut readonly_fields = Vec::new();
     let mut writeonly_fields = Vec::new();
+    let mut foreign_key_models = Vec::new();
+    let mut typed_column_fields = Vec::new();
+    let mut typed_column_values = Vec::new();
+    let struct_name = name.to_string();
     if let Data::Struct(data) = input.data && let Fields::Named(fields) = data.fields {
         for field in fields.named.into_iter() {
             let mut type_name = parser::get_type_name(&field.ty);
@@ -88,6 +169,16 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                 let mut default_value = None;
                 let mut index_type = None;
                 let mut reference = None;
+                let mut foreign_key = false;
+                let mut on_delete = None;
+                let mut on_update = None;
+                let mut required = false;
+                let mut format = None;
+                let mut pattern = None;
+                let mut minimum = None;
+                let mut maximum = None;
+                let mut enum_values = Vec::new();
+                let mut enum_type = false;
                 'inner: for attr in field.attrs.iter() {
                     let arguments = parser::parse_schema_attr(attr);
                     for (key, value) in arguments.into_iter() {
@@ -128,9 +219,48 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                             "reference" => {
                                 reference = value;
                             }
+                            "foreign_key" => {
+                                foreign_key = value.map(|v| v == "true").unwrap_or(true);
+                            }
+                            "on_delete" => {
+                                on_delete = value;
+                            }
+                            "on_update" => {
+                                on_update = value;
+                            }
                             "primary_key" => {
                                 primary_key_name = name.clone();
                             }
+                            "required" => {
+                                required = value.map(|v| v == "true").unwrap_or(true);
+                            }
+                            "format" => {
+                                format = value;
+                            }
+                            "pattern" => {
+                                if let Some(ref value) = value {
+                                    parser::check_regex_pattern(value);
+                                }
+                                pattern = value;
+                            }
+                            "minimum" => {
+                                minimum = value.and_then(|v| v.parse::<f64>().ok());
+                            }
+                            "maximum" => {
+                                maximum = value.and_then(|v| v.parse::<f64>().ok());
+                            }
+                            "enum_values" => {
+                                if let Some(value) = value {
+                                    enum_values = value
+                                        .split(',')
+                                        .map(|v| v.trim().to_owned())
+                                        .filter(|v| !v.is_empty())
+                                        .collect();
+                                }
+                            }
+                            "enum_type" => {
+                                enum_type = true;
+                            }
                             "readonly" => {
                                 readonly_fields.push(quote!{ #name });
                             }
@@ -144,6 +274,23 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                 if ignore {
                     continue;
                 }
+                let quote_enum_type = if enum_type {
+                    type_name = if parser::check_option_type(&type_name) {
+                        "Option<String>".to_owned()
+                    } else {
+                        "String".to_owned()
+                    };
+
+                    let enum_ty = parser::strip_option_type(&field.ty);
+                    quote! {
+                        column.set_enum_type(
+                            <#enum_ty as zino_core::model::EnumColumn>::TYPE_NAME,
+                            <#enum_ty as zino_core::model::EnumColumn>::VARIANTS,
+                        );
+                    }
+                } else {
+                    quote! {}
+                };
                 if primary_key_name == name {
                     primary_key_type = type_name.clone();
                     not_null = true;
@@ -174,16 +321,74 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                 };
                 let quote_reference = if let Some(ref model_name) = reference {
                     let model_ident = format_ident!("{}", model_name);
+                    let foreign_key = (foreign_key || on_delete.is_some() || on_update.is_some())
+                        && !parser::check_vec_type(&type_name);
+                    let quote_on_delete = if let Some(action) = on_delete {
+                        quote! { reference.set_on_delete(#action); }
+                    } else {
+                        quote! {}
+                    };
+                    let quote_on_update = if let Some(action) = on_update {
+                        quote! { reference.set_on_update(#action); }
+                    } else {
+                        quote! {}
+                    };
+                    let quote_foreign_key = if foreign_key {
+                        if *model_name != struct_name && !foreign_key_models.contains(model_name) {
+                            foreign_key_models.push(model_name.clone());
+                        }
+                        quote! {
+                            reference.set_foreign_key(true);
+                            #quote_on_delete
+                            #quote_on_update
+                        }
+                    } else {
+                        quote! {}
+                    };
                     quote! {{
                         let table_name = <#model_ident>::table_name();
                         let column_name = <#model_ident>::PRIMARY_KEY_NAME;
-                        Some(zino_core::model::Reference::new(table_name, column_name))
+                        let mut reference = zino_core::model::Reference::new(table_name, column_name);
+                        #quote_foreign_key
+                        Some(reference)
                     }}
                 } else {
                     quote! { None }
                 };
+                let quote_format = if let Some(format) = format {
+                    quote! { column.set_format(#format); }
+                } else {
+                    quote! {}
+                };
+                let quote_pattern = if let Some(pattern) = pattern {
+                    quote! { column.set_pattern(#pattern); }
+                } else {
+                    quote! {}
+                };
+                let quote_minimum = if let Some(minimum) = minimum {
+                    quote! { column.set_minimum(#minimum); }
+                } else {
+                    quote! {}
+                };
+                let quote_maximum = if let Some(maximum) = maximum {
+                    quote! { column.set_maximum(#maximum); }
+                } else {
+                    quote! {}
+                };
+                let quote_enum_values = if enum_values.is_empty() {
+                    quote! {}
+                } else {
+                    quote! { column.set_enum_values(&[#(#enum_values),*]); }
+                };
                 let column = quote! {{
                     let mut column = zino_core::model::Column::new(#name, #type_name, #not_null);
+                    column.set_required(#required);
+                    #quote_format
+                    #quote_pattern
+                    #quote_minimum
+                    #quote_maximum
+                    #quote_enum_values
+                    #quote_enum_type
                     if let Some(default_value) = #quote_value {
                         column.set_default_value(default_value);
                     }
@@ -195,6 +400,15 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                     }
                     column
                 }};
+                let field_type = field.ty;
+                let doc = format!("The `{name}` column.");
+                typed_column_fields.push(quote! {
+                    #[doc = #doc]
+                    pub #ident: zino_core::model::TypedColumn<#field_type>
+                });
+                typed_column_values.push(quote! {
+                    #ident: zino_core::model::TypedColumn::new(#name)
+                });
                 columns.push(column);
                 column_fields.push(quote! { #name });
             }
@@ -212,8 +426,13 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
     let schema_writeonly_fields = format_ident!("{}_WRITEONLY_FIELDS", model_name_upper_snake);
     let schema_reader = format_ident!("{}_READER", model_name_upper_snake);
     let schema_writer = format_ident!("{}_WRITER", model_name_upper_snake);
+    let schema_indexes = format_ident!("{}_INDEXES", model_name_upper_snake);
     let avro_schema = format_ident!("{}_AVRO_SCHEMA", model_name_upper_snake);
+    let typed_columns = format_ident!("{}Columns", name);
+    let typed_columns_doc = format!("Typed columns of the `{name}` model.");
+    let vis = input.vis;
     let num_columns = columns.len();
+    let num_indexes = indexes.len();
     let num_readonly_fields = readonly_fields.len();
     let num_writeonly_fields = writeonly_fields.len();
     let quote_table_name = if let Some(table_name) = table_name {
@@ -221,16 +440,30 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
     } else {
         quote! { None }
     };
+    let quote_tenant_id_field = if let Some(tenant_id_field) = tenant_id_field {
+        quote! { Some(#tenant_id_field) }
+    } else {
+        quote! { None }
+    };
+    let quote_cache_ttl = if let Some(secs) = cache_ttl {
+        quote! { Some(std::time::Duration::from_secs(#secs)) }
+    } else {
+        quote! { None }
+    };
     let quote_documentation = if let Some(doc) = documentation {
         quote! { Some(#doc) }
     } else {
         quote! { None }
     };
+    let foreign_key_models = foreign_key_models
+        .into_iter()
+        .map(|model_name| format_ident!("{}", model_name))
+        .collect::<Vec<_>>();
     let output = quote! {
         use zino_core::{
             database::{self, ConnectionPool, Schema},
             error::Error as ZinoError,
-            model::{schema, Column},
+            model::{schema, Column, Index},
         };
 
         static #avro_schema: std::sync::LazyLock<schema::Schema> = std::sync::LazyLock::new(|| {
@@ -256,6 +489,8 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
         });
         static #schema_columns: std::sync::LazyLock<[Column; #num_columns]> =
             std::sync::LazyLock::new(|| [#(#columns),*]);
+        static #schema_indexes: std::sync::LazyLock<[Index; #num_indexes]> =
+            std::sync::LazyLock::new(|| [#(#indexes),*]);
         static #schema_fields: std::sync::LazyLock<[&str; #num_columns]> =
             std::sync::LazyLock::new(|| [#(#column_fields),*]);
         static #schema_readonly_fields: std::sync::LazyLock<[&str; #num_readonly_fields]> =
@@ -273,6 +508,8 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
             const READER_NAME: &'static str = #reader_name;
             const WRITER_NAME: &'static str = #writer_name;
             const TABLE_NAME: Option<&'static str> = #quote_table_name;
+            const TENANT_KEY_NAME: Option<&'static str> = #quote_tenant_id_field;
+            const QUERY_CACHE_TTL: Option<std::time::Duration> = #quote_cache_ttl;
 
             #[inline]
             fn primary_key(&self) -> &Self::PrimaryKey {
@@ -294,6 +531,11 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
                 #schema_fields.as_slice()
             }
 
+            #[inline]
+            fn indexes() -> &'static [Index<'static>] {
+                #schema_indexes.as_slice()
+            }
+
             #[inline]
             fn readonly_fields() -> &'static [&'static str] {
                 #schema_readonly_fields.as_slice()
@@ -305,49 +547,91 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
             }
 
             async fn acquire_reader() -> Result<&'static ConnectionPool, ZinoError> {
-                if let Some(connection_pool) = #schema_reader.get() {
-                    Ok(*connection_pool)
+                let connection_pool = if let Some(connection_pool) = #schema_reader.get() {
+                    *connection_pool
                 } else {
-                    let model_name = Self::MODEL_NAME;
-                    let connection_pool = Self::init_reader()?;
-                    if let Err(err) = Self::create_table().await {
-                        let message = format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`");
-                        connection_pool.store_availability(false);
-                        return Err(err.context(message));
-                    }
-                    if let Err(err) = Self::create_indexes().await {
-                        let message = format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`");
-                        connection_pool.store_availability(false);
-                        return Err(err.context(message));
-                    }
-                    #schema_reader.set(connection_pool).map_err(|_| {
-                        ZinoError::new(format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`"))
-                    })?;
-                    Ok(connection_pool)
+                    // Bootstraps the tables outside of the tenant scope so that the shared pool
+                    // is never bound to the pool or schema of a tenant.
+                    zino_core::database::TenantContext::all_tenants()
+                        .scope(async {
+                            let model_name = Self::MODEL_NAME;
+                            let connection_pool = Self::init_reader()?;
+                            #(
+                                if let Err(err) = <#foreign_key_models>::create_table().await {
+                                    let message = format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`");
+                                    connection_pool.store_availability(false);
+                                    return Err(err.context(message));
+                                }
+                            )*
+                            if let Err(err) = Self::create_table().await {
+                                let message = format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`");
+                                connection_pool.store_availability(false);
+                                return Err(err.context(message));
+                            }
+                            if let Err(err) = Self::create_indexes().await {
+                                let message = format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`");
+                                connection_pool.store_availability(false);
+                                return Err(err.context(message));
+                            }
+                            #schema_reader.set(connection_pool).map_err(|_| {
+                                ZinoError::new(format!("503 Service Unavailable: fail to acquire reader for the model `{model_name}`"))
+                            })?;
+                            Ok(connection_pool)
+                        })
+                        .await?
+                };
+                if zino_core::database::TenantContext::current()
+                    .is_some_and(|ctx| ctx.pool_name().is_some())
+                {
+                    // The pool of the tenant should never fall back to a shared one.
+                    return Self::init_reader();
                 }
+                // Selects the pool again for load balancing and failover.
+                Ok(Self::init_reader().unwrap_or(connection_pool))
             }
 
             async fn acquire_writer() -> Result<&'static ConnectionPool, ZinoError> {
-                if let Some(connection_pool) = #schema_writer.get() {
-                    Ok(*connection_pool)
+                let connection_pool = if let Some(connection_pool) = #schema_writer.get() {
+                    *connection_pool
                 } else {
-                    let model_name = Self::MODEL_NAME;
-                    let connection_pool = Self::init_writer()?;
-                    if let Err(err) = Self::create_table().await {
-                        let message = format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`");
-                        connection_pool.store_availability(false);
-                        return Err(err.context(message));
-                    }
-                    if let Err(err) = Self::create_indexes().await {
-                        let message = format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`");
-                        connection_pool.store_availability(false);
-                        return Err(err.context(message));
-                    }
-                    #schema_writer.set(connection_pool).map_err(|_| {
-                        ZinoError::new(format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`"))
-                    })?;
-                    Ok(connection_pool)
+                    // Bootstraps the tables outside of the tenant scope so that the shared pool
+                    // is never bound to the pool or schema of a tenant.
+                    zino_core::database::TenantContext::all_tenants()
+                        .scope(async {
+                            let model_name = Self::MODEL_NAME;
+                            let connection_pool = Self::init_writer()?;
+                            #(
+                                if let Err(err) = <#foreign_key_models>::create_table().await {
+                                    let message = format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`");
+                                    connection_pool.store_availability(false);
+                                    return Err(err.context(message));
+                                }
+                            )*
+                            if let Err(err) = Self::create_table().await {
+                                let message = format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`");
+                                connection_pool.store_availability(false);
+                                return Err(err.context(message));
+                            }
+                            if let Err(err) = Self::create_indexes().await {
+                                let message = format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`");
+                                connection_pool.store_availability(false);
+                                return Err(err.context(message));
+                            }
+                            #schema_writer.set(connection_pool).map_err(|_| {
+                                ZinoError::new(format!("503 Service Unavailable: fail to acquire writer for the model `{model_name}`"))
+                            })?;
+                            Ok(connection_pool)
+                        })
+                        .await?
+                };
+                if zino_core::database::TenantContext::current()
+                    .is_some_and(|ctx| ctx.pool_name().is_some())
+                {
+                    // The pool of the tenant should never fall back to a shared one.
+                    return Self::init_writer();
                 }
+                // Selects the pool again for load balancing and failover.
+                Ok(Self::init_writer().unwrap_or(connection_pool))
             }
         }
 
@@ -359,6 +643,225 @@ pub fn schema_macro(item: TokenStream) -> TokenStream {
         }
 
         impl Eq for #name {}
+
+        #[doc = #typed_columns_doc]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #typed_columns {
+            #(#typed_column_fields),*
+        }
+
+        impl #name {
+            /// Typed columns of the model for compile-checked queries and mutations.
+            #vis const COL: #typed_columns = #typed_columns {
+                #(#typed_column_values),*
+            };
+        }
+    };
+
+    TokenStream::from(output)
+}
+
+/// Derive the `Model` trait.
+///
+/// The `read_map` method is generated from the field types.
+/// Fields marked as `ignore`, `readonly` or `skip_read` are skipped except for the primary key,
+/// and so are the revision fields `created_at`, `updated_at`, `version` and `edition`
+/// which are maintained by the framework. Type errors are recorded under the field name.
+#[proc_macro_derive(Model, attributes(schema))]
+pub fn model_macro(item: TokenStream) -> TokenStream {
+    /// Types which can be parsed by the `JsonObjectExt` methods
+    const PARSABLE_TYPES: [&str; 10] = [
+        "u64", "i64", "u32", "i32", "u16", "u8", "usize", "f64", "f32", "bool",
+    ];
+
+    /// Types which can be parsed from a string
+    const FROM_STR_TYPES: [&str; 16] = [
+        "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8", "usize", "isize", "f64", "f32",
+        "bool", "String", "Uuid", "DateTime",
+    ];
+
+    /// Revision fields maintained by the framework
+    const REVISION_FIELDS: [&str; 4] = ["created_at", "updated_at", "version", "edition"];
+
+    // Input
+    let input = parse_macro_input!(item as DeriveInput);
+
+    // Parsing field attrs
+    let name = input.ident;
+    let mut read_fields = Vec::new();
+    let mut primary_key_type = String::from("Uuid");
+    let mut primary_key_name = String::from("id");
+    if let Data::Struct(data) = input.data && let Fields::Named(fields) = data.fields {
+        let mut model_fields = Vec::new();
+        for field in fields.named.into_iter() {
+            let type_name = parser::get_type_name(&field.ty);
+            if let Some(ident) = field.ident && !type_name.is_empty() {
+                let name = ident.to_string();
+                let mut ignore = false;
+                let mut readonly = false;
+                for attr in field.attrs.iter() {
+                    let arguments = parser::parse_schema_attr(attr);
+                    for (key, _value) in arguments.into_iter() {
+                        match key.as_str() {
+                            "ignore" => {
+                                ignore = true;
+                            }
+                            "readonly" | "skip_read" => {
+                                readonly = true;
+                            }
+                            "primary_key" => {
+                                primary_key_name = name.clone();
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                if !ignore {
+                    model_fields.push((ident, name, field.ty, type_name, readonly));
+                }
+            }
+        }
+        for (ident, name, ty, type_name, readonly) in model_fields.into_iter() {
+            if name == primary_key_name {
+                primary_key_type = type_name.clone();
+            } else if readonly || REVISION_FIELDS.contains(&name.as_str()) {
+                continue;
+            }
+
+            let inner_type_name = type_name
+                .split_once('<')
+                .map(|(_, s)| s.trim_end_matches('>'))
+                .unwrap_or_default();
+            if type_name == "String" {
+                read_fields.push(quote! {
+                    if let Some(value) = data.parse_string(#name) {
+                        self.#ident = value.into_owned();
+                    }
+                });
+            } else if type_name == "Option<String>" {
+                read_fields.push(quote! {
+                    if let Some(value) = data.parse_string(#name) {
+                        self.#ident = Some(value.into_owned());
+                    }
+                });
+            } else if type_name == "Uuid" || type_name == "DateTime" {
+                let parser_ident = format_ident!("parse_{}", type_name.to_lowercase());
+                read_fields.push(quote! {
+                    if let Some(result) = data.#parser_ident(#name) {
+                        match result {
+                            Ok(value) => self.#ident = value,
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            } else if type_name == "Option<Uuid>" || type_name == "Option<DateTime>" {
+                let parser_ident = format_ident!("parse_{}", inner_type_name.to_lowercase());
+                read_fields.push(quote! {
+                    if let Some(result) = data.#parser_ident(#name) {
+                        match result {
+                            Ok(value) => self.#ident = Some(value),
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            } else if PARSABLE_TYPES.contains(&type_name.as_str()) {
+                let parser_ident = format_ident!("parse_{}", type_name);
+                read_fields.push(quote! {
+                    if let Some(result) = data.#parser_ident(#name) {
+                        match result {
+                            Ok(value) => self.#ident = value,
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            } else if parser::check_option_type(&type_name)
+                && PARSABLE_TYPES.contains(&inner_type_name)
+            {
+                let parser_ident = format_ident!("parse_{}", inner_type_name);
+                read_fields.push(quote! {
+                    if let Some(result) = data.#parser_ident(#name) {
+                        match result {
+                            Ok(value) => self.#ident = Some(value),
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            } else if type_name == "Vec<String>" {
+                read_fields.push(quote! {
+                    if let Some(values) = data.parse_str_array(#name) {
+                        self.#ident = values
+                            .into_iter()
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_owned())
+                            .collect();
+                    }
+                });
+            } else if parser::check_vec_type(&type_name)
+                && FROM_STR_TYPES.contains(&inner_type_name)
+            {
+                let inner_type_ident = format_ident!("{}", inner_type_name);
+                read_fields.push(quote! {
+                    let has_non_str_values = data
+                        .get_array(#name)
+                        .is_some_and(|values| values.iter().any(|v| !v.is_string()));
+                    if has_non_str_values {
+                        if let Some(result) = data.deserialize_value(#name) {
+                            match result {
+                                Ok(vec) => self.#ident = vec,
+                                Err(err) => validation.record_fail(#name, err),
+                            }
+                        }
+                    } else if let Some(values) = data.parse_str_array(#name) {
+                        let result = values
+                            .into_iter()
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.parse::<#inner_type_ident>())
+                            .collect::<Result<Vec<_>, _>>();
+                        match result {
+                            Ok(vec) => self.#ident = vec,
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            } else {
+                read_fields.push(quote! {
+                    if let Some(result) = data.deserialize_value::<#ty>(#name) {
+                        match result {
+                            Ok(value) => self.#ident = value,
+                            Err(err) => validation.record_fail(#name, err),
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    // Output
+    let model_primary_key = format_ident!("{}", primary_key_name);
+    let new_model = if primary_key_type == "Uuid" {
+        quote! {
+            Self {
+                #model_primary_key: zino_core::Uuid::new_v4(),
+                ..Self::default()
+            }
+        }
+    } else {
+        quote! { Self::default() }
+    };
+    let output = quote! {
+        impl zino_core::model::Model for #name {
+            #[inline]
+            fn new() -> Self {
+                #new_model
+            }
+
+            fn read_map(&mut self, data: &zino_core::Map) -> zino_core::request::Validation {
+                use zino_core::extension::JsonObjectExt as _;
+                let mut validation = zino_core::request::Validation::new();
+                #(#read_fields)*
+                validation
+            }
+        }
     };
 
     TokenStream::from(output)
@@ -382,20 +885,39 @@ pub fn decode_row_macro(item: TokenStream) -> TokenStream {
         for field in fields.named.into_iter() {
             let type_name = parser::get_type_name(&field.ty);
             if let Some(ident) = field.ident && !type_name.is_empty() {
+                let name = ident.to_string();
                 let mut ignore = false;
+                let mut enum_type = false;
                 'inner: for attr in field.attrs.iter() {
                     let arguments = parser::parse_schema_attr(attr);
                     for (key, _value) in arguments.iter() {
                         if key == "ignore" || key == "writeonly" {
                             ignore = true;
                             break 'inner;
+                        } else if key == "enum_type" {
+                            enum_type = true;
                         }
                     }
                 }
                 if ignore {
                     continue;
                 }
-                if type_name == "Map" {
+                if enum_type {
+                    let enum_ty = parser::strip_option_type(&field.ty);
+                    if parser::check_option_type(&type_name) {
+                        decode_model_fields.push(quote! {
+                            if let Some(value) = database::decode::<Option<String>>(row, #name)? {
+                                let value = <#enum_ty as EnumColumn>::from_column_value(&value)?;
+                                model.#ident = Some(value);
+                            }
+                        });
+                    } else {
+                        decode_model_fields.push(quote! {
+                            let value = database::decode::<String>(row, #name)?;
+                            model.#ident = <#enum_ty as EnumColumn>::from_column_value(&value)?;
+                        });
+                    }
+                } else if type_name == "Map" {
                     decode_model_fields.push(quote! {
                         if let JsonValue::Object(map) = database::decode(row, #name)? {
                             model.#ident = map;
@@ -440,7 +962,7 @@ pub fn decode_row_macro(item: TokenStream) -> TokenStream {
             type Error = zino_core::error::Error;
 
             fn decode_row(row: &DatabaseRow) -> Result<Self, Self::Error> {
-                use zino_core::{extension::JsonValueExt, JsonValue};
+                use zino_core::{extension::JsonValueExt, model::EnumColumn, JsonValue};
                 let mut model = <#name>::default();
                 #(#decode_model_fields)*
                 if cfg!(feature = "orm-mysql") {
@@ -456,15 +978,127 @@ pub fn decode_row_macro(item: TokenStream) -> TokenStream {
     TokenStream::from(output)
 }
 
+/// Derive the `EnumColumn` trait for an enum with unit variants.
+#[proc_macro_derive(EnumColumn, attributes(schema))]
+pub fn enum_column_macro(item: TokenStream) -> TokenStream {
+    // Input
+    let input = parse_macro_input!(item as DeriveInput);
+
+    // Parsing enum attrs
+    let name = input.ident;
+    let mut type_name = name.to_string().to_case(Case::Snake);
+    for attr in input.attrs.iter() {
+        for (key, value) in parser::parse_schema_attr(attr).into_iter() {
+            if let Some(value) = value && key == "type_name" {
+                type_name = value;
+            }
+        }
+    }
+
+    // Parsing variants
+    let mut variants = Vec::new();
+    let mut variant_idents = Vec::new();
+    if let Data::Enum(data) = input.data {
+        for variant in data.variants.into_iter() {
+            if let Fields::Unit = variant.fields {
+                variants.push(variant.ident.to_string());
+                variant_idents.push(variant.ident);
+            }
+        }
+    }
+
+    // Output
+    let enum_name = name.to_string();
+    let output = quote! {
+        impl zino_core::model::EnumColumn for #name {
+            const TYPE_NAME: &'static str = #type_name;
+            const VARIANTS: &'static [&'static str] = &[#(#variants),*];
+
+            fn as_column_value(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #variants,)*
+                }
+            }
+
+            fn from_column_value(value: &str) -> Result<Self, zino_core::error::Error> {
+                match value {
+                    #(#variants => Ok(Self::#variant_idents),)*
+                    _ => {
+                        let message = format!("invalid value `{value}` for the enum `{}`", #enum_name);
+                        Err(zino_core::error::Error::new(message))
+                    }
+                }
+            }
+        }
+    };
+
+    TokenStream::from(output)
+}
+
 /// Derive the `ModelAccessor` trait.
+///
+/// Relations are declared with the `belongs_to` and `many_to_many` field attributes
+/// or the `has_many(name, model, foreign_key)` model attribute, which generate typed loaders
+/// such as `group.load_members()`. The relations listed in the `include` option
+/// of the query are eager loaded by `fetch` and `fetch_by_id_with`.
 #[proc_macro_derive(ModelAccessor, attributes(schema))]
 pub fn model_accessor_macro(item: TokenStream) -> TokenStream {
+    /// Numeric types
+    const NUMERIC_TYPES: [&str; 12] = [
+        "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8", "usize", "isize", "f64", "f32",
+    ];
+
     // Input
     let input = parse_macro_input!(item as DeriveInput);
 
     // Parsing struct attrs
     let mut compound_constraints = Vec::new();
+    let mut relation_methods = Vec::new();
+    let mut eager_loading_queries = Vec::new();
     for attr in input.attrs.iter() {
+        for arguments in parser::parse_schema_list_attr(attr, "has_many").into_iter() {
+            let mut relation = None;
+            let mut model_name = None;
+            let mut foreign_key = None;
+            for (key, value) in arguments.into_iter() {
+                match key.as_str() {
+                    "name" => {
+                        relation = value;
+                    }
+                    "model" => {
+                        model_name = value;
+                    }
+                    "foreign_key" => {
+                        foreign_key = value;
+                    }
+                    _ => (),
+                }
+            }
+            if let Some(relation) = relation
+                && let Some(model_name) = model_name
+                && let Some(foreign_key) = foreign_key
+            {
+                let model_ident = format_ident!("{}", model_name);
+                let method_ident = format_ident!("load_{}", relation);
+                let doc = format!("Loads the related `{model_name}` models of `{relation}`.");
+                relation_methods.push(quote! {
+                    #[doc = #doc]
+                    pub async fn #method_ident(&self) -> Result<Vec<#model_ident>, ZinoError> {
+                        let query = <#model_ident>::default_query();
+                        let values = vec![self.id().to_string().into()];
+                        <#model_ident>::find_related_as(&query, #foreign_key, values).await
+                    }
+                });
+                eager_loading_queries.push(quote! {
+                    if query.is_included(#relation) {
+                        let mut query = <#model_ident>::default_query();
+                        query.add_filter("translate", translate_enabled);
+                        let local_key = Self::PRIMARY_KEY_NAME;
+                        <#model_ident>::eager_load(&mut query, &mut models, local_key, #foreign_key, #relation, true).await?;
+                    }
+                });
+            }
+        }
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if let Some(value) = value && key == "unique_on" {
                 let mut fields = Vec::new();
@@ -544,6 +1178,66 @@ pub fn model_accessor_macro(item: TokenStream) -> TokenStream {
                                 }
                                 snapshot_fields.push(field);
                             }
+                            "belongs_to" | "many_to_many" => {
+                                if let Some(value) = value {
+                                    let model_ident = format_ident!("{}", value);
+                                    let many = key == "many_to_many";
+                                    let relation = if many {
+                                        name.as_str()
+                                    } else {
+                                        name.strip_suffix("_id").unwrap_or(&name)
+                                    };
+                                    let method_ident = format_ident!("load_{}", relation);
+                                    let values = if parser::check_option_type(&type_name)
+                                        || parser::check_vec_type(&type_name)
+                                    {
+                                        quote! {
+                                            self.#ident.iter().map(|v| v.to_string()).collect::<Vec<_>>()
+                                        }
+                                    } else {
+                                        quote! { vec![self.#ident.to_string()] }
+                                    };
+                                    let doc = format!("Loads the related `{value}` of `{relation}`.");
+                                    if many {
+                                        relation_methods.push(quote! {
+                                            #[doc = #doc]
+                                            pub async fn #method_ident(&self) -> Result<Vec<#model_ident>, ZinoError> {
+                                                let values = #values;
+                                                if values.is_empty() {
+                                                    return Ok(Vec::new());
+                                                }
+                                                let query = <#model_ident>::default_query();
+                                                let primary_key_name = <#model_ident>::PRIMARY_KEY_NAME;
+                                                let values = values.into_iter().map(|v| v.into()).collect();
+                                                <#model_ident>::find_related_as(&query, primary_key_name, values).await
+                                            }
+                                        });
+                                    } else {
+                                        relation_methods.push(quote! {
+                                            #[doc = #doc]
+                                            pub async fn #method_ident(&self) -> Result<Option<#model_ident>, ZinoError> {
+                                                use zino_core::extension::JsonObjectExt as _;
+                                                let values = #values;
+                                                if values.is_empty() {
+                                                    return Ok(None);
+                                                }
+                                                let mut query = <#model_ident>::default_query();
+                                                let primary_key_name = <#model_ident>::PRIMARY_KEY_NAME;
+                                                query.add_filter(primary_key_name, ZinoMap::from_entry("$in", values));
+                                                <#model_ident>::find_one_as(&query).await
+                                            }
+                                        });
+                                    }
+                                    eager_loading_queries.push(quote! {
+                                        if query.is_included(#relation) {
+                                            let mut query = <#model_ident>::default_query();
+                                            query.add_filter("translate", translate_enabled);
+                                            let foreign_key = <#model_ident>::PRIMARY_KEY_NAME;
+                                            <#model_ident>::eager_load(&mut query, &mut models, #name, foreign_key, #relation, #many).await?;
+                                        }
+                                    });
+                                }
+                            }
                             "reference" => {
                                 if let Some(value) = value {
                                     let model_ident = format_ident!("{}", value);
@@ -775,6 +1469,162 @@ pub fn model_accessor_macro(item: TokenStream) -> TokenStream {
                                     });
                                 }
                             }
+                            "required" if is_readable => {
+                                if parser::check_option_type(&type_name) {
+                                    field_constraints.push(quote! {
+                                        if self.#ident.is_none() {
+                                            validation.record(#name, "it is required");
+                                        }
+                                    });
+                                } else if type_name == "Uuid" {
+                                    field_constraints.push(quote! {
+                                        if self.#ident.is_nil() {
+                                            validation.record(#name, "it is required");
+                                        }
+                                    });
+                                } else if parser::check_vec_type(&type_name) ||
+                                    matches!(type_name.as_str(), "String" | "Map")
+                                {
+                                    field_constraints.push(quote! {
+                                        if self.#ident.is_empty() {
+                                            validation.record(#name, "it is required");
+                                        }
+                                    });
+                                }
+                            }
+                            "format" => {
+                                if let Some(format) = value {
+                                    if type_name == "String" {
+                                        field_constraints.push(quote! {
+                                            let value = self.#ident.as_str();
+                                            if !value.is_empty() &&
+                                                let Err(err) = ZinoValidation::validate_format(#format, value)
+                                            {
+                                                validation.record_fail(#name, err);
+                                            }
+                                        });
+                                    } else if type_name == "Option<String>" {
+                                        field_constraints.push(quote! {
+                                            if let Some(value) = self.#ident.as_deref() &&
+                                                let Err(err) = ZinoValidation::validate_format(#format, value)
+                                            {
+                                                validation.record_fail(#name, err);
+                                            }
+                                        });
+                                    } else if type_name == "Vec<String>" {
+                                        field_constraints.push(quote! {
+                                            for value in self.#ident.iter() {
+                                                if let Err(err) = ZinoValidation::validate_format(#format, value) {
+                                                    validation.record_fail(#name, err);
+                                                    break;
+                                                }
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            "pattern" => {
+                                if let Some(pattern) = value {
+                                    parser::check_regex_pattern(&pattern);
+                                    if type_name == "String" {
+                                        field_constraints.push(quote! {
+                                            let value = self.#ident.as_str();
+                                            if !value.is_empty() &&
+                                                let Err(err) = ZinoValidation::validate_pattern(#pattern, value)
+                                            {
+                                                validation.record_fail(#name, err);
+                                            }
+                                        });
+                                    } else if type_name == "Option<String>" {
+                                        field_constraints.push(quote! {
+                                            if let Some(value) = self.#ident.as_deref() &&
+                                                let Err(err) = ZinoValidation::validate_pattern(#pattern, value)
+                                            {
+                                                validation.record_fail(#name, err);
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            "minimum" => {
+                                if let Some(minimum) = value.and_then(|v| v.parse::<f64>().ok()) {
+                                    if NUMERIC_TYPES.contains(&type_name.as_str()) {
+                                        field_constraints.push(quote! {
+                                            let minimum = #minimum;
+                                            if (self.#ident as f64) < minimum {
+                                                let message = format!("it should be at least {minimum}");
+                                                validation.record(#name, message);
+                                            }
+                                        });
+                                    } else if let Some(type_name) = type_name.strip_prefix("Option<") &&
+                                        NUMERIC_TYPES.contains(&type_name.trim_end_matches('>'))
+                                    {
+                                        field_constraints.push(quote! {
+                                            let minimum = #minimum;
+                                            if let Some(value) = self.#ident && (value as f64) < minimum {
+                                                let message = format!("it should be at least {minimum}");
+                                                validation.record(#name, message);
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            "maximum" => {
+                                if let Some(maximum) = value.and_then(|v| v.parse::<f64>().ok()) {
+                                    if NUMERIC_TYPES.contains(&type_name.as_str()) {
+                                        field_constraints.push(quote! {
+                                            let maximum = #maximum;
+                                            if (self.#ident as f64) > maximum {
+                                                let message = format!("it should be at most {maximum}");
+                                                validation.record(#name, message);
+                                            }
+                                        });
+                                    } else if let Some(type_name) = type_name.strip_prefix("Option<") &&
+                                        NUMERIC_TYPES.contains(&type_name.trim_end_matches('>'))
+                                    {
+                                        field_constraints.push(quote! {
+                                            let maximum = #maximum;
+                                            if let Some(value) = self.#ident && (value as f64) > maximum {
+                                                let message = format!("it should be at most {maximum}");
+                                                validation.record(#name, message);
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            "enum_values" => {
+                                if let Some(value) = value {
+                                    let enum_values = value
+                                        .split(',')
+                                        .map(|v| v.trim())
+                                        .filter(|v| !v.is_empty())
+                                        .collect::<Vec<_>>();
+                                    let message = format!("it should be one of `{}`", enum_values.join("`, `"));
+                                    if type_name == "String" {
+                                        field_constraints.push(quote! {
+                                            let value = self.#ident.as_str();
+                                            if !value.is_empty() && ![#(#enum_values),*].contains(&value) {
+                                                validation.record(#name, #message);
+                                            }
+                                        });
+                                    } else if type_name == "Option<String>" {
+                                        field_constraints.push(quote! {
+                                            if let Some(value) = self.#ident.as_deref() &&
+                                                ![#(#enum_values),*].contains(&value)
+                                            {
+                                                validation.record(#name, #message);
+                                            }
+                                        });
+                                    } else if type_name == "Vec<String>" {
+                                        field_constraints.push(quote! {
+                                            let enum_values = [#(#enum_values),*];
+                                            if self.#ident.iter().any(|v| !enum_values.contains(&v.as_str())) {
+                                                validation.record(#name, #message);
+                                            }
+                                        });
+                                    }
+                                }
+                            }
                             _ => (),
                         }
                     }
@@ -924,24 +1774,38 @@ pub fn model_accessor_macro(item: TokenStream) -> TokenStream {
             for (model, ref_fields) in model_references.into_iter() {
                 let model_ident = format_ident!("{}", model);
                 let populated_query = quote! {
-                    let mut query = #model_ident::default_snapshot_query();
-                    query.add_filter("translate", translate_enabled);
-                    #model_ident::populate(&mut query, &mut models, [#(#ref_fields),*]).await?;
+                    {
+                        let mut query = #model_ident::default_snapshot_query();
+                        query.add_filter("translate", translate_enabled);
+                        #model_ident::populate(&mut query, &mut models, [#(#ref_fields),*]).await?;
+                    }
                 };
                 let populated_one_query = quote! {
-                    let mut query = #model_ident::default_query();
-                    query.add_filter("translate", true);
-                    #model_ident::populate_one(&mut query, &mut model, [#(#ref_fields),*]).await?;
+                    {
+                        let mut query = #model_ident::default_query();
+                        query.add_filter("translate", true);
+                        #model_ident::populate_one(&mut query, &mut model, [#(#ref_fields),*]).await?;
+                    }
                 };
                 populated_queries.push(populated_query);
                 populated_one_queries.push(populated_one_query);
             }
         }
+        populated_queries.extend(eager_loading_queries.iter().cloned());
         populated_queries.push(quote! { Ok(models) });
-        populated_one_queries.push(quote! { Ok(model) });
     }
 
     // Output
+    let (query_arg, eager_loading_one_query) = if eager_loading_queries.is_empty() {
+        (format_ident!("_query"), quote! {})
+    } else {
+        let eager_loading_one_query = quote! {
+            let translate_enabled = true;
+            let mut models = std::slice::from_mut(&mut model);
+            #(#eager_loading_queries)*
+        };
+        (format_ident!("query"), eager_loading_one_query)
+    };
     let model_primary_key_type = format_ident!("{}", primary_key_type);
     let model_primary_key = format_ident!("{}", primary_key_name);
     let model_user_id_type = format_ident!("{}", user_id_type);
@@ -993,10 +1857,24 @@ pub fn model_accessor_macro(item: TokenStream) -> TokenStream {
                 #(#populated_queries)*
             }
 
+            #[inline]
             async fn fetch_by_id(id: &#model_primary_key_type) -> Result<ZinoMap, ZinoError> {
+                Self::fetch_by_id_with(id, &Query::default()).await
+            }
+
+            async fn fetch_by_id_with(
+                id: &#model_primary_key_type,
+                #query_arg: &Query,
+            ) -> Result<ZinoMap, ZinoError> {
                 #(#populated_one_queries)*
+                #eager_loading_one_query
+                Ok(model)
             }
         }
+
+        impl #name {
+            #(#relation_methods)*
+        }
     };
 
     TokenStream::from(output)
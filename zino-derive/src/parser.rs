@@ -1,5 +1,6 @@
 use syn::{
     punctuated::Punctuated, Attribute, Expr, GenericArgument, Lit, Meta, PathArguments, Token, Type,
+    UnOp,
 };
 
 /// Returns `true` if the type is `Vec<T>`.
@@ -18,6 +19,28 @@ pub(super) fn check_option_type(type_name: &str) -> bool {
         .is_some_and(|(t, s)| t == "Option" && s.ends_with('>'))
 }
 
+/// Returns the inner type `T` of `Option<T>`, or the type itself.
+pub(super) fn strip_option_type(ty: &Type) -> &Type {
+    if let Type::Path(path) = ty
+        && let Some(segment) = path.path.segments.last()
+        && segment.ident == "Option"
+        && let PathArguments::AngleBracketed(ref generics) = segment.arguments
+        && let Some(GenericArgument::Type(ref ty)) = generics.args.first()
+    {
+        ty
+    } else {
+        ty
+    }
+}
+
+/// Checks the syntax of a regex pattern, which fails the derive for an invalid one
+/// instead of leaving the error to the runtime validation.
+pub(super) fn check_regex_pattern(pattern: &str) {
+    if let Err(err) = regex_syntax::Parser::new().parse(pattern) {
+        panic!("invalid regex pattern `{pattern}`: {err}");
+    }
+}
+
 /// Returns the type name as a str.
 pub(super) fn get_type_name(ty: &Type) -> String {
     if let Type::Path(ty) = ty && let Some(segment) = ty.path.segments.last() {
@@ -41,18 +64,7 @@ pub(super) fn parse_schema_attr(attr: &Attribute) -> Vec<(String, Option<String>
             for meta in nested {
                 if let Some(ident) = meta.path().get_ident() {
                     let key = ident.to_string();
-                    let value = if let Meta::NameValue(name_value) = meta
-                        && let Expr::Lit(expr_lit) = name_value.value
-                    {
-                        match expr_lit.lit {
-                            Lit::Str(ref lit_str) => Some(lit_str.value()),
-                            Lit::Bool(ref lit_bool) => Some(lit_bool.value.to_string()),
-                            Lit::Int(ref lit_int) => Some(lit_int.base10_digits().to_string()),
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    };
+                    let value = parse_meta_value(meta);
                     arguments.push((key, value));
                 }
             }
@@ -60,3 +72,69 @@ pub(super) fn parse_schema_attr(attr: &Attribute) -> Vec<(String, Option<String>
     }
     arguments
 }
+
+/// Parses an attribute and returns a list of arguments for each nested list
+/// with the specific name, such as `#[schema(index(columns = "a, b", unique))]`.
+pub(super) fn parse_schema_list_attr(
+    attr: &Attribute,
+    name: &str,
+) -> Vec<Vec<(String, Option<String>)>> {
+    let mut lists = Vec::new();
+    if attr.path().is_ident("schema") {
+        if let Ok(nested) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) {
+            for meta in nested {
+                if let Meta::List(list) = meta
+                    && list.path.is_ident(name)
+                    && let Ok(nested) =
+                        list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                {
+                    let mut arguments = Vec::new();
+                    for meta in nested {
+                        if let Some(ident) = meta.path().get_ident() {
+                            let key = ident.to_string();
+                            let value = parse_meta_value(meta);
+                            arguments.push((key, value));
+                        }
+                    }
+                    lists.push(arguments);
+                }
+            }
+        }
+    }
+    lists
+}
+
+/// Parses the literal value of a name-value meta.
+/// An array of literals, such as `enum_values = ["a", "b"]`, is joined with commas.
+fn parse_meta_value(meta: Meta) -> Option<String> {
+    if let Meta::NameValue(name_value) = meta {
+        parse_expr_value(&name_value.value)
+    } else {
+        None
+    }
+}
+
+/// Parses the value of a literal expression.
+fn parse_expr_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(expr_lit) => match expr_lit.lit {
+            Lit::Str(ref lit_str) => Some(lit_str.value()),
+            Lit::Bool(ref lit_bool) => Some(lit_bool.value.to_string()),
+            Lit::Int(ref lit_int) => Some(lit_int.base10_digits().to_owned()),
+            Lit::Float(ref lit_float) => Some(lit_float.base10_digits().to_owned()),
+            _ => None,
+        },
+        Expr::Unary(expr_unary) if matches!(expr_unary.op, UnOp::Neg(_)) => {
+            parse_expr_value(&expr_unary.expr).map(|value| format!("-{value}"))
+        }
+        Expr::Array(expr_array) => {
+            let values = expr_array
+                .elems
+                .iter()
+                .filter_map(parse_expr_value)
+                .collect::<Vec<_>>();
+            Some(values.join(","))
+        }
+        _ => None,
+    }
+}
@@ -40,9 +40,9 @@ pub struct Group {
     description: String,
 
     // Info fields.
-    #[schema(reference = "User")]
+    #[schema(reference = "User", belongs_to = "User")]
     manager_id: Uuid, // user.id
-    #[schema(reference = "User", index_type = "gin")]
+    #[schema(reference = "User", many_to_many = "User", index_type = "gin")]
     members: Vec<Uuid>, // user.id
     #[cfg(feature = "tags")]
     #[schema(reference = "Tag", index_type = "gin")]
@@ -22,6 +22,107 @@
 #![feature(let_chains)]
 #![forbid(unsafe_code)]
 
+/// Implements the model hooks to record the audit trail in the history table,
+/// in which [`AuditEntry`](zino_core::database::AuditEntry) is used as the model data.
+#[cfg(feature = "audit")]
+macro_rules! impl_audit_hooks {
+    () => {
+        type Data = zino_core::database::AuditEntry;
+
+        #[inline]
+        async fn after_create_table() -> Result<(), zino_core::error::Error> {
+            <Self as zino_core::database::AuditTrail>::create_history_table().await
+        }
+
+        #[inline]
+        async fn before_insert(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            Ok(zino_core::database::AuditEntry::new(self))
+        }
+
+        #[inline]
+        async fn after_insert(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::Insert)
+                .await
+        }
+
+        #[inline]
+        async fn before_update(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            zino_core::database::AuditEntry::capture(self).await
+        }
+
+        #[inline]
+        async fn after_update(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::Update)
+                .await
+        }
+
+        #[inline]
+        async fn before_delete(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            zino_core::database::AuditEntry::capture(self).await
+        }
+
+        #[inline]
+        async fn after_delete(
+            self,
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::Delete)
+                .await
+        }
+
+        #[inline]
+        async fn before_delete_many(
+            query: &zino_core::model::Query,
+        ) -> Result<Self::Data, zino_core::error::Error> {
+            zino_core::database::AuditEntry::capture_many::<Self>(query).await
+        }
+
+        #[inline]
+        async fn after_delete_many(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::Delete)
+                .await
+        }
+
+        #[inline]
+        async fn before_soft_delete(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            zino_core::database::AuditEntry::capture(self).await
+        }
+
+        #[inline]
+        async fn after_soft_delete(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::SoftDelete)
+                .await
+        }
+
+        #[inline]
+        async fn before_upsert(&mut self) -> Result<Self::Data, zino_core::error::Error> {
+            zino_core::database::AuditEntry::capture(self).await
+        }
+
+        #[inline]
+        async fn after_upsert(
+            ctx: &zino_core::model::QueryContext,
+            data: Self::Data,
+        ) -> Result<(), zino_core::error::Error> {
+            data.record::<Self>(ctx, zino_core::database::AuditAction::Upsert)
+                .await
+        }
+    };
+}
+
 pub mod group;
 pub mod policy;
 pub mod resource;
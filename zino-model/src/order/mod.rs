@@ -119,6 +119,9 @@ impl Model for Order {
 }
 
 impl ModelHooks for Order {
+    #[cfg(feature = "audit")]
+    impl_audit_hooks!();
+
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
 
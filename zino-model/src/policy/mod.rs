@@ -25,6 +25,7 @@ use zino_core::auth::UserSession;
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Schema, ModelAccessor)]
 #[serde(rename_all = "snake_case")]
 #[serde(default)]
+#[cfg_attr(feature = "tenant-isolation", schema(tenant_id_field = "tenant_id"))]
 pub struct Policy {
     // Basic fields.
     #[schema(readonly)]
@@ -121,6 +122,9 @@ impl Model for Policy {
 }
 
 impl ModelHooks for Policy {
+    #[cfg(feature = "audit")]
+    impl_audit_hooks!();
+
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
 
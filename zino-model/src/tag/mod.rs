@@ -1,26 +1,20 @@
 //! The `tag` model and related services.
 
 use serde::{Deserialize, Serialize};
-use zino_core::{
-    datetime::DateTime,
-    error::Error,
-    extension::JsonObjectExt,
-    model::{Model, ModelHooks},
-    request::Validation,
-    Map, Uuid,
-};
-use zino_derive::{ModelAccessor, Schema};
+use zino_core::{datetime::DateTime, error::Error, model::ModelHooks, Map, Uuid};
+use zino_derive::{Model, ModelAccessor, Schema};
 
 #[cfg(any(feature = "owner-id", feature = "maintainer-id"))]
 use crate::user::User;
 
 #[cfg(feature = "maintainer-id")]
-use zino_core::auth::UserSession;
+use zino_core::{auth::UserSession, extension::JsonObjectExt};
 
 /// The `tag` model.
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Schema, ModelAccessor)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Schema, ModelAccessor, Model)]
 #[serde(rename_all = "snake_case")]
 #[serde(default)]
+#[schema(has_many(name = "children", model = "Tag", foreign_key = "parent_id"))]
 pub struct Tag {
     // Basic fields.
     #[schema(readonly)]
@@ -28,12 +22,12 @@ pub struct Tag {
     #[schema(not_null, index_type = "text")]
     name: String,
     #[cfg(feature = "namespace")]
-    #[schema(default_value = "Tag::model_namespace", index_type = "hash")]
+    #[schema(skip_read, default_value = "Tag::model_namespace", index_type = "hash")]
     namespace: String,
     #[cfg(feature = "visibility")]
-    #[schema(default_value = "Internal")]
+    #[schema(skip_read, default_value = "Internal")]
     visibility: String,
-    #[schema(default_value = "Active", index_type = "hash")]
+    #[schema(skip_read, default_value = "Active", index_type = "hash")]
     status: String,
     #[schema(index_type = "text")]
     description: String,
@@ -41,11 +35,13 @@ pub struct Tag {
     // Info fields.
     #[schema(not_null)]
     category: String,
-    #[schema(reference = "Tag")]
+    #[schema(reference = "Tag", belongs_to = "Tag")]
     parent_id: Option<Uuid>, // tag.id, tag.namespace = {tag.namespace}, tag.category = {tag.category}
 
     // Extensions.
+    #[schema(skip_read)]
     content: Map,
+    #[schema(skip_read)]
     extra: Map,
 
     // Revisions.
@@ -64,56 +60,6 @@ pub struct Tag {
     edition: u32,
 }
 
-impl Model for Tag {
-    #[inline]
-    fn new() -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            ..Self::default()
-        }
-    }
-
-    fn read_map(&mut self, data: &Map) -> Validation {
-        let mut validation = Validation::new();
-        if let Some(result) = data.parse_uuid("id") {
-            match result {
-                Ok(id) => self.id = id,
-                Err(err) => validation.record_fail("id", err),
-            }
-        }
-        if let Some(name) = data.parse_string("name") {
-            self.name = name.into_owned();
-        }
-        if let Some(description) = data.parse_string("description") {
-            self.description = description.into_owned();
-        }
-        if let Some(category) = data.parse_string("category") {
-            self.category = category.into_owned();
-        }
-        if let Some(result) = data.parse_uuid("parent_id") {
-            match result {
-                Ok(parent_id) => self.parent_id = Some(parent_id),
-                Err(err) => validation.record_fail("parent_id", err),
-            }
-        }
-        #[cfg(feature = "owner-id")]
-        if let Some(result) = data.parse_uuid("owner_id") {
-            match result {
-                Ok(owner_id) => self.owner_id = Some(owner_id),
-                Err(err) => validation.record_fail("owner_id", err),
-            }
-        }
-        #[cfg(feature = "maintainer-id")]
-        if let Some(result) = data.parse_uuid("maintainer_id") {
-            match result {
-                Ok(maintainer_id) => self.maintainer_id = Some(maintainer_id),
-                Err(err) => validation.record_fail("maintainer_id", err),
-            }
-        }
-        validation
-    }
-}
-
 impl Tag {
     /// Returns the `category`.
     #[inline]
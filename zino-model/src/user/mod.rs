@@ -44,13 +44,9 @@ pub struct User {
     #[schema(default_value = "User::model_namespace", index_type = "hash")]
     namespace: String,
     #[cfg(feature = "visibility")]
-    #[schema(column_type = "String", default_value = "UserVisibility::default")]
+    #[schema(enum_type, default_value = "UserVisibility::default")]
     visibility: UserVisibility,
-    #[schema(
-        column_type = "String",
-        default_value = "UserStatus::default",
-        index_type = "hash"
-    )]
+    #[schema(enum_type, default_value = "UserStatus::default", index_type = "hash")]
     status: UserStatus,
     #[schema(index_type = "text")]
     description: String,
@@ -65,13 +61,16 @@ pub struct User {
     #[schema(not_null, writeonly)]
     password: String,
     nickname: String,
+    #[schema(format = "uri")]
     avatar: String,
+    #[schema(format = "uri")]
     website: String,
+    #[schema(format = "email")]
     email: String,
     location: String,
     locale: String,
     mobile: String,
-    #[schema(snapshot, index_type = "gin")]
+    #[schema(required, snapshot, index_type = "gin")]
     roles: Vec<String>,
     #[cfg(feature = "tags")]
     #[schema(reference = "Tag", index_type = "gin")]
@@ -143,9 +142,6 @@ impl Model for User {
                 validation.record_fail("roles", err);
             }
         }
-        if self.roles.is_empty() && !validation.contains_key("roles") {
-            validation.record("roles", "should be nonempty");
-        }
         #[cfg(feature = "tags")]
         if let Some(tags) = data.parse_array("tags") {
             self.tags = tags;
@@ -169,6 +165,9 @@ impl Model for User {
 }
 
 impl ModelHooks for User {
+    #[cfg(feature = "audit")]
+    impl_audit_hooks!();
+
     #[cfg(feature = "maintainer-id")]
     type Extension = UserSession<Uuid, String>;
 
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display, IntoStaticStr};
 use zino_core::JsonValue;
+use zino_derive::EnumColumn;
 
 /// User status.
 #[derive(
@@ -16,6 +17,7 @@ use zino_core::JsonValue;
     AsRefStr,
     Display,
     IntoStaticStr,
+    EnumColumn,
 )]
 #[non_exhaustive]
 pub enum UserStatus {
@@ -39,3 +41,24 @@ impl From<UserStatus> for JsonValue {
         value.as_ref().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::UserStatus;
+    use zino_core::{model::EnumColumn, JsonValue};
+
+    #[test]
+    fn it_round_trips_enum_column_values() {
+        assert_eq!(UserStatus::TYPE_NAME, "user_status");
+        for &value in UserStatus::VARIANTS {
+            let status = UserStatus::from_column_value(value).unwrap();
+            assert_eq!(status.as_column_value(), value);
+            assert_eq!(JsonValue::from(status), value);
+        }
+        assert_eq!(
+            UserStatus::default().as_column_value(),
+            UserStatus::VARIANTS[0]
+        );
+        assert!(UserStatus::from_column_value("Unknown").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display, IntoStaticStr};
 use zino_core::JsonValue;
+use zino_derive::EnumColumn;
 
 /// User visibility.
 #[derive(
@@ -16,6 +17,7 @@ use zino_core::JsonValue;
     AsRefStr,
     Display,
     IntoStaticStr,
+    EnumColumn,
 )]
 #[non_exhaustive]
 pub enum UserVisibility {
@@ -54,6 +54,15 @@ impl Application for ActixCluster {
             }
         });
 
+        #[cfg(feature = "orm")]
+        runtime.spawn(async {
+            let interval = zino_core::database::health_check_interval();
+            loop {
+                rt::time::sleep(interval).await;
+                zino_core::database::check_connection_pools().await;
+            }
+        });
+
         runtime.block_on(async {
             let default_routes = self.default_routes.leak() as &'static [_];
             let named_routes = self.named_routes.leak() as &'static [_];
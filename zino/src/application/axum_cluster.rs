@@ -77,6 +77,15 @@ impl Application for AxumCluster {
             }
         });
 
+        #[cfg(feature = "orm")]
+        runtime.spawn(async {
+            let interval = zino_core::database::health_check_interval();
+            loop {
+                tokio::time::sleep(interval).await;
+                zino_core::database::check_connection_pools().await;
+            }
+        });
+
         runtime.block_on(async {
             let default_routes = self.default_routes;
             let named_routes = self.named_routes;
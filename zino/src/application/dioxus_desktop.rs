@@ -64,6 +64,15 @@ where
             }
         });
 
+        #[cfg(feature = "orm")]
+        runtime.spawn(async {
+            let interval = zino_core::database::health_check_interval();
+            loop {
+                tokio::time::sleep(interval).await;
+                zino_core::database::check_connection_pools().await;
+            }
+        });
+
         let mut app_window = WindowBuilder::new()
             .with_title(Self::name())
             .with_maximized(true)
@@ -12,14 +12,26 @@ pub trait DefaultController<K, U = K> {
     async fn delete(req: Self::Request) -> Self::Result;
 
     /// Updates a model.
+    ///
+    /// A version control conflict in optimistic locking is rejected with `409 Conflict`.
     async fn update(req: Self::Request) -> Self::Result;
 
     /// Views a model.
     async fn view(req: Self::Request) -> Self::Result;
 
     /// Lists models.
+    ///
+    /// The keyset pagination is enabled by the `cursor` query parameter,
+    /// which should be empty for the first page.
     async fn list(req: Self::Request) -> Self::Result;
 
+    /// Computes the statistics of models.
+    ///
+    /// The aggregation is specified by the query parameters such as `group_by=status`,
+    /// `bucket=created_at|month` and `count_distinct=tags`, and the remaining ones are
+    /// the filters for the models.
+    async fn stats(req: Self::Request) -> Self::Result;
+
     /// Batch inserts multiple models.
     async fn batch_insert(req: Self::Request) -> Self::Result;
 
@@ -27,23 +39,49 @@ pub trait DefaultController<K, U = K> {
     async fn batch_delete(req: Self::Request) -> Self::Result;
 
     /// Imports model data.
+    ///
+    /// In the `upsert` mode, the models are upserted in batches, and the conflict target
+    /// can be specified by the `conflict_target` query parameter.
     async fn import(req: Self::Request) -> Self::Result;
 
     /// Exports model data.
+    ///
+    /// The `csv` and `jsonlines` formats are streamed from the database,
+    /// and the other formats are exported after all the models are fetched.
     async fn export(req: Self::Request) -> Self::Result;
 }
 
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+use futures::{channel::mpsc, stream::BoxStream, SinkExt, StreamExt};
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+use std::mem;
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+use tokio::{runtime::Handle, task};
 #[cfg(any(feature = "actix", feature = "axum"))]
 #[cfg(feature = "orm")]
 use zino_core::{
-    database::{ModelAccessor, ModelHelper},
+    database::{AuditContext, ModelAccessor, ModelHelper, Schema},
+    error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{ModelHooks, Query},
+    model::{Aggregation, ModelHooks, Query},
     request::RequestContext,
     response::{ExtractRejection, Rejection, StatusCode},
     JsonValue, Map,
 };
 
+/// Number of models to be upserted in a batch when importing data.
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Number of models to be buffered in the streamed export.
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+const EXPORT_BUFFER_SIZE: usize = 64;
+
 #[cfg(any(feature = "actix", feature = "axum"))]
 #[cfg(feature = "orm")]
 impl<K, U, M: ModelAccessor<K, U>> DefaultController<K, U> for M
@@ -67,7 +105,10 @@ where
         Self::after_decode(&mut model_snapshot)
             .await
             .extract(&req)?;
-        model.insert().await.extract(&req)?;
+        audit_context(&req)
+            .scope(model.insert())
+            .await
+            .extract(&req)?;
 
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         Self::translate_model(&mut model_snapshot);
@@ -81,7 +122,10 @@ where
 
     async fn delete(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
-        Self::soft_delete_by_id(&id).await.extract(&req)?;
+        audit_context(&req)
+            .scope(Self::soft_delete_by_id(&id))
+            .await
+            .extract(&req)?;
 
         let res = crate::Response::default().context(&req);
         Ok(res.into())
@@ -92,7 +136,8 @@ where
         let mut body = req.parse_body().await?;
 
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
-        let (validation, model) = Self::update_by_id(&id, &mut body, extension)
+        let (validation, model) = audit_context(&req)
+            .scope(Self::update_by_id(&id, &mut body, extension))
             .await
             .extract(&req)?;
         let mut res = crate::Response::from(validation).context(&req);
@@ -105,7 +150,11 @@ where
 
     async fn view(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
-        let model = Self::fetch_by_id(&id).await.extract(&req)?;
+        let mut query = Query::default();
+        if let Some(includes) = req.get_query("include") {
+            query.set_includes(&includes.split(',').map(|s| s.trim()).collect::<Vec<_>>());
+        }
+        let model = Self::fetch_by_id_with(&id, &query).await.extract(&req)?;
         let mut res = crate::Response::default().context(&req);
         res.set_json_data(Map::data_entry(model));
         Ok(res.into())
@@ -119,8 +168,34 @@ where
             .extract(&req)?;
 
         let mut res = req.query_validation(&mut query)?;
+        let cursor_enabled = req.get_query("cursor").is_some();
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        if cursor_enabled
+            && !query
+                .sort_order()
+                .iter()
+                .any(|(field, _)| field == primary_key_name)
+        {
+            query.set_sort_order(primary_key_name, true);
+        }
+        if cursor_enabled {
+            if let Some((field, _)) = query
+                .sort_order()
+                .iter()
+                .find(|(field, _)| !Self::has_column(field))
+            {
+                let err = Error::new(format!("the column `{field}` does not exist"));
+                return Err(Rejection::from_validation_entry("order_by", err)
+                    .context(&req)
+                    .into());
+            }
+            query.add_sort_fields();
+        }
+
+        let mut next_cursor = None;
         let models = if query.populate_enabled() {
             let mut models = Self::fetch(&query).await.extract(&req)?;
+            next_cursor = query.next_cursor(&models);
             for model in models.iter_mut() {
                 Self::before_respond(model, extension.as_ref())
                     .await
@@ -128,7 +203,8 @@ where
             }
             models
         } else {
-            let mut models = Self::find(&query).await.extract(&req)?;
+            let mut models = Self::find_cached(&query).await.extract(&req)?;
+            next_cursor = query.next_cursor(&models);
             let translate_enabled = query.translate_enabled();
             for model in models.iter_mut() {
                 Self::after_decode(model).await.extract(&req)?;
@@ -141,14 +217,42 @@ where
         };
 
         let mut data = Map::data_entries(models);
-        if req.get_query("page_size").is_some() && req.get_query("total_rows").is_none() {
-            let total_rows = Self::count(&query).await.extract(&req)?;
+        if req.get_query("page_size").is_some()
+            && req.get_query("total_rows").is_none()
+            && query.cursor().is_none()
+        {
+            let total_rows = Self::count_cached(&query).await.extract(&req)?;
             data.upsert("total_rows", total_rows);
         }
+        if cursor_enabled {
+            data.upsert("next_cursor", next_cursor);
+        }
         res.set_json_data(data);
         Ok(res.into())
     }
 
+    async fn stats(req: Self::Request) -> Self::Result {
+        let mut query = Self::default_query();
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::before_list(&mut query, extension.as_ref())
+            .await
+            .extract(&req)?;
+
+        let mut res = req.query_validation(&mut query)?;
+        let data = req.parse_query::<Map>()?;
+        let mut aggregation = Aggregation::new();
+        let validation = aggregation.read_map(&data);
+        if !validation.is_success() {
+            return Err(Rejection::bad_request(validation).context(&req).into());
+        }
+
+        let stats = Self::aggregate::<Map>(&query, &aggregation)
+            .await
+            .extract(&req)?;
+        res.set_json_data(Map::data_entries(stats));
+        Ok(res.into())
+    }
+
     async fn batch_insert(mut req: Self::Request) -> Self::Result {
         let data = req.parse_body::<Vec<Map>>().await?;
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
@@ -187,7 +291,10 @@ where
             res.set_data(&validations);
             Ok(res.into())
         } else {
-            let rows_affected = Self::insert_many(models).await.extract(&req)?;
+            let rows_affected = audit_context(&req)
+                .scope(Self::insert_many(models))
+                .await
+                .extract(&req)?;
             let data = Map::from_entry("rows_affected", rows_affected);
             let mut res = crate::Response::default().context(&req);
             res.set_code(StatusCode::CREATED);
@@ -205,7 +312,10 @@ where
             Map::from_entry(Self::PRIMARY_KEY_NAME, primary_key_values)
         };
         let query = Query::new(filters);
-        let rows_affected = Self::delete_many(&query).await.extract(&req)?;
+        let rows_affected = audit_context(&req)
+            .scope(Self::delete_many(&query))
+            .await
+            .extract(&req)?;
         let data = Map::from_entry("rows_affected", rows_affected);
         let mut res = crate::Response::default().context(&req);
         res.set_json_data(data);
@@ -214,8 +324,32 @@ where
 
     async fn import(mut req: Self::Request) -> Self::Result {
         let is_upsert_mode = req.get_query("mode").is_some_and(|s| s == "upsert");
+        let conflict_fields = req
+            .get_query("conflict_target")
+            .map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let conflict_target = conflict_fields
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+        if let Some(field) = conflict_target
+            .iter()
+            .find(|field| !Self::has_column(field))
+        {
+            let err = Error::new(format!("the column `{field}` does not exist"));
+            return Err(Rejection::from_validation_entry("conflict_target", err)
+                .context(&req)
+                .into());
+        }
+
         let data = req.parse_body::<Vec<Map>>().await?;
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        let audit_context = audit_context(&req);
+        let mut models = Vec::new();
         let mut rows_affected = 0;
         for (index, mut map) in data.into_iter().enumerate() {
             Self::before_extract()
@@ -239,11 +373,23 @@ where
                         .map_err(|err| Rejection::from_error(err).context(&req))?;
                 }
                 if is_upsert_mode {
-                    model.upsert().await.extract(&req)?;
+                    models.push(model);
+                    if models.len() >= IMPORT_BATCH_SIZE {
+                        let models = mem::take(&mut models);
+                        rows_affected += audit_context
+                            .clone()
+                            .scope(Self::upsert_many(models, &conflict_target, &[]))
+                            .await
+                            .extract(&req)?;
+                    }
                 } else {
-                    model.insert().await.extract(&req)?;
+                    audit_context
+                        .clone()
+                        .scope(model.insert())
+                        .await
+                        .extract(&req)?;
+                    rows_affected += 1;
                 }
-                rows_affected += 1;
             } else {
                 let mut map = validation.into_map();
                 map.upsert("index", index);
@@ -253,6 +399,12 @@ where
                 return Ok(res.into());
             }
         }
+        if !models.is_empty() {
+            rows_affected += audit_context
+                .scope(Self::upsert_many(models, &conflict_target, &[]))
+                .await
+                .extract(&req)?;
+        }
 
         let data = Map::from_entry("rows_affected", rows_affected);
         let mut res = crate::Response::default().context(&req);
@@ -268,8 +420,22 @@ where
             .extract(&req)?;
 
         let mut res = req.query_validation(&mut query)?;
-        let mut models = Self::find(&query).await.extract(&req)?;
         let translate_enabled = query.translate_enabled();
+        let format = req.get_query("format").unwrap_or("json");
+        if matches!(format, "csv" | "jsonlines") {
+            let stream = Self::find_stream::<Map>(&query).await.extract(&req)?;
+            let translate_model =
+                translate_enabled.then_some(Self::translate_model as fn(&mut Map));
+            let receiver = prepare_export_stream::<Self>(stream, translate_model, extension);
+            if format == "csv" {
+                res.set_csv_stream_response(receiver);
+            } else {
+                res.set_jsonlines_stream_response(receiver);
+            }
+            return Ok(res.into());
+        }
+
+        let mut models = Self::find(&query).await.extract(&req)?;
         for model in models.iter_mut() {
             Self::after_decode(model).await.extract(&req)?;
             translate_enabled.then(|| Self::translate_model(model));
@@ -278,10 +444,7 @@ where
                 .extract(&req)?;
         }
 
-        let format = req.get_query("format").unwrap_or("json");
         match format {
-            "csv" => res.set_csv_response(models),
-            "jsonlines" => res.set_jsonlines_response(models),
             "msgpack" => res.set_msgpack_response(models),
             #[cfg(feature = "export-pdf")]
             "pdf" => {
@@ -303,3 +466,61 @@ where
         Ok(res.into())
     }
 }
+
+/// Prepares the models of the streamed export.
+/// The futures of the model hooks are not required to be `Send`,
+/// so they are driven by the runtime handle on a blocking thread.
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+fn prepare_export_stream<M: Schema>(
+    mut stream: BoxStream<'static, Result<Map, Error>>,
+    translate_model: Option<fn(&mut Map)>,
+    extension: Option<<M as ModelHooks>::Extension>,
+) -> mpsc::Receiver<Result<Map, Error>> {
+    let (mut sender, receiver) = mpsc::channel(EXPORT_BUFFER_SIZE);
+    let handle = Handle::current();
+    task::spawn_blocking(move || {
+        handle.block_on(async move {
+            while let Some(item) = stream.next().await {
+                let item = match item {
+                    Ok(mut model) => {
+                        let extension = extension.as_ref();
+                        prepare_export_model::<M>(&mut model, translate_model, extension)
+                            .await
+                            .map(|_| model)
+                    }
+                    Err(err) => Err(err),
+                };
+                if sender.send(item).await.is_err() {
+                    break;
+                }
+            }
+        })
+    });
+    receiver
+}
+
+/// Prepares the model data for the streamed export.
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+async fn prepare_export_model<M: Schema>(
+    model: &mut Map,
+    translate_model: Option<fn(&mut Map)>,
+    extension: Option<&<M as ModelHooks>::Extension>,
+) -> Result<(), Error> {
+    M::after_decode(model).await?;
+    if let Some(translate_model) = translate_model {
+        translate_model(model);
+    }
+    M::before_respond(model, extension).await
+}
+
+/// Returns the audit context of the request.
+/// The actor is expected to be set by a middleware such as the user session initializer.
+#[cfg(any(feature = "actix", feature = "axum"))]
+#[cfg(feature = "orm")]
+fn audit_context(req: &crate::Request) -> AuditContext {
+    let mut ctx = req.get_data::<AuditContext>().unwrap_or_default();
+    ctx.set_request_id(req.request_id());
+    ctx
+}
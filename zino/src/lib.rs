@@ -83,6 +83,9 @@ cfg_if::cfg_if! {
         use request::actix_request::ActixExtractor;
         use response::actix_response::{ActixRejection, ActixResponse};
 
+        #[cfg(feature = "orm")]
+        pub use middleware::TenantContextInitializer;
+
         /// HTTP server cluster for `actix-web`.
         pub type Cluster = ActixCluster;
 
@@ -106,6 +109,9 @@ cfg_if::cfg_if! {
 
         pub use channel::axum_channel::MessageChannel;
 
+        #[cfg(feature = "orm")]
+        pub use middleware::tenant_context;
+
         /// HTTP server cluster for `axum`.
         pub type Cluster = AxumCluster;
 
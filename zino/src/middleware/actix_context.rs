@@ -51,10 +51,41 @@ where
             req.extensions_mut().insert(ctx);
         }
 
-        let fut = self.service.call(req.into());
-        Box::pin(async move {
-            let res = fut.await?;
-            Ok(res)
-        })
+        #[cfg(feature = "orm")]
+        {
+            use actix_web::http::header::{HeaderName, HeaderValue};
+            use zino_core::{database::QueryStats, request::Context};
+
+            let request_id = req
+                .extensions()
+                .get::<Context>()
+                .map(|ctx| ctx.request_id())
+                .unwrap_or_default();
+            let stats = QueryStats::new(request_id);
+            let fut = stats.clone().scope(self.service.call(req.into()));
+            Box::pin(async move {
+                let mut res = fut.await?;
+                if let Some(metric) = stats.timing_metric() {
+                    let headers = res.headers_mut();
+                    let server_timing =
+                        match headers.get("server-timing").and_then(|v| v.to_str().ok()) {
+                            Some(value) => format!("{value}, {metric}"),
+                            None => metric.to_string(),
+                        };
+                    if let Ok(value) = HeaderValue::try_from(server_timing) {
+                        headers.insert(HeaderName::from_static("server-timing"), value);
+                    }
+                }
+                Ok(res)
+            })
+        }
+        #[cfg(not(feature = "orm"))]
+        {
+            let fut = self.service.call(req.into());
+            Box::pin(async move {
+                let res = fut.await?;
+                Ok(res)
+            })
+        }
     }
 }
@@ -0,0 +1,84 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use std::{
+    fmt::Display,
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+};
+use zino_core::{auth::UserSession, database::TenantContext, request::RequestContext, Uuid};
+
+/// Middleware initializer which runs the request in the scope of the tenant
+/// specified by the [`UserSession`].
+///
+/// It should be wrapped inside the middleware which sets the user session as the request data.
+pub struct TenantContextInitializer<U = Uuid> {
+    /// Type of the user ID.
+    phantom: PhantomData<U>,
+}
+
+impl<U> Default for TenantContextInitializer<U> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, B, U> Transform<S, ServiceRequest> for TenantContextInitializer<U>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    U: Clone + Display + Send + Sync + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = TenantContextMiddleware<S, U>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TenantContextMiddleware {
+            service,
+            phantom: PhantomData,
+        }))
+    }
+}
+
+pub struct TenantContextMiddleware<S, U> {
+    service: S,
+    phantom: PhantomData<U>,
+}
+
+impl<S, B, U> Service<ServiceRequest> for TenantContextMiddleware<S, U>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    U: Clone + Display + Send + Sync + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let req = crate::Request::from(req);
+        let tenant_context = req
+            .get_data::<UserSession<U>>()
+            .and_then(|session| session.tenant_id().map(TenantContext::new));
+
+        let fut = self.service.call(req.into());
+        Box::pin(async move {
+            let res = if let Some(tenant_context) = tenant_context {
+                tenant_context.scope(fut).await?
+            } else {
+                fut.await?
+            };
+            Ok(res)
+        })
+    }
+}
@@ -8,5 +8,31 @@ pub(crate) async fn request_context(req: crate::Request, next: Next<Body>) -> Re
     if let Some(ctx) = new_context {
         req.extensions_mut().insert(ctx);
     }
+
+    #[cfg(feature = "orm")]
+    {
+        use axum::http::HeaderValue;
+        use zino_core::{database::QueryStats, request::Context};
+
+        let request_id = req
+            .extensions()
+            .get::<Context>()
+            .map(|ctx| ctx.request_id())
+            .unwrap_or_default();
+        let stats = QueryStats::new(request_id);
+        let mut res = stats.clone().scope(next.run(req)).await;
+        if let Some(metric) = stats.timing_metric() {
+            let headers = res.headers_mut();
+            let server_timing = match headers.get("server-timing").and_then(|v| v.to_str().ok()) {
+                Some(value) => format!("{value}, {metric}"),
+                None => metric.to_string(),
+            };
+            if let Ok(value) = HeaderValue::try_from(server_timing) {
+                headers.insert("server-timing", value);
+            }
+        }
+        res
+    }
+    #[cfg(not(feature = "orm"))]
     next.run(req).await
 }
@@ -0,0 +1,20 @@
+use axum::{body::Body, middleware::Next, response::Response};
+use std::fmt::Display;
+use zino_core::{auth::UserSession, database::TenantContext, request::RequestContext};
+
+/// Runs the request in the scope of the tenant specified by the [`UserSession`].
+///
+/// It should be layered inside the middleware which sets the user session as the request data.
+pub async fn tenant_context<U>(req: crate::Request, next: Next<Body>) -> Response
+where
+    U: Clone + Display + Send + Sync + 'static,
+{
+    let tenant_context = req
+        .get_data::<UserSession<U>>()
+        .and_then(|session| session.tenant_id().map(TenantContext::new));
+    if let Some(tenant_context) = tenant_context {
+        tenant_context.scope(next.run(req.into())).await
+    } else {
+        next.run(req.into()).await
+    }
+}
@@ -5,19 +5,31 @@ cfg_if::cfg_if! {
         mod actix_etag;
         mod actix_tracing;
 
+        #[cfg(feature = "orm")]
+        mod actix_tenant;
+
         pub(crate) use self::actix_context::RequestContextInitializer;
         pub(crate) use self::actix_cors::cors_middleware;
         pub(crate) use self::actix_etag::ETagFinalizer;
         pub(crate) use self::actix_tracing::tracing_middleware;
+
+        #[cfg(feature = "orm")]
+        pub use self::actix_tenant::TenantContextInitializer;
     } else if #[cfg(feature = "axum")] {
         mod axum_context;
         mod axum_etag;
         mod tower_cors;
         mod tower_tracing;
 
+        #[cfg(feature = "orm")]
+        mod axum_tenant;
+
         pub(crate) use self::axum_context::request_context;
         pub(crate) use self::axum_etag::etag_middleware;
         pub(crate) use self::tower_cors::CORS_MIDDLEWARE;
         pub(crate) use self::tower_tracing::TRACING_MIDDLEWARE;
+
+        #[cfg(feature = "orm")]
+        pub use self::axum_tenant::tenant_context;
     }
 }
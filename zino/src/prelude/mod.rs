@@ -12,7 +12,10 @@ pub use zino_core::{
     error::Error,
     extension::{JsonObjectExt, JsonValueExt, TomlTableExt},
     file::NamedFile,
-    model::{Model, ModelHooks, Mutation, MutationBuilder, Query, QueryBuilder, QueryContext},
+    model::{
+        Model, ModelHooks, Mutation, MutationBuilder, Query, QueryBuilder, QueryContext,
+        TypedColumn,
+    },
     reject,
     request::{RequestContext, Validation},
     response::{ExtractRejection, Rejection, StatusCode, WebHook},
@@ -23,4 +26,4 @@ pub use zino_core::{
 
 #[cfg(feature = "orm")]
 #[doc(no_inline)]
-pub use zino_core::database::{ModelAccessor, ModelHelper, Schema};
+pub use zino_core::database::{AuditContext, ModelAccessor, ModelHelper, Schema, TenantContext};
@@ -6,9 +6,10 @@ use actix_web::{
     },
     HttpRequest, HttpResponse, Responder, ResponseError,
 };
-use std::fmt;
+use futures::TryStreamExt;
+use std::{fmt, io};
 use zino_core::{
-    response::{Rejection, Response, ResponseCode},
+    response::{BytesStream, Rejection, Response, ResponseCode},
     trace::TimingMetric,
 };
 
@@ -32,7 +33,11 @@ impl Responder for ActixResponse<StatusCode> {
             response = response.context(&req);
         }
 
-        let mut res = build_http_response(&mut response);
+        let mut res = if let Some(stream) = response.take_stream_data() {
+            build_stream_response(&response, stream)
+        } else {
+            build_http_response(&mut response)
+        };
         for (key, value) in response.finalize() {
             if let Ok(header_name) = HeaderName::try_from(key.as_ref()) &&
                 let Ok(header_value) = HeaderValue::try_from(value)
@@ -144,3 +149,20 @@ fn build_http_response(response: &mut Response<StatusCode>) -> HttpResponse<BoxB
         }
     }
 }
+
+/// Build a streaming http response from `zino_core::response::Response`.
+fn build_stream_response(
+    response: &Response<StatusCode>,
+    stream: BytesStream,
+) -> HttpResponse<BoxBody> {
+    let status_code = response
+        .status_code()
+        .try_into()
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let stream = stream.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()));
+    let mut res = HttpResponse::build(status_code).streaming(stream);
+    if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
+        res.headers_mut().insert(header::CONTENT_TYPE, header_value);
+    }
+    res
+}